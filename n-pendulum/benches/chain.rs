@@ -0,0 +1,91 @@
+// Steps per second of the chain solver for every link count, as a baseline
+// against regressions: `cargo bench -p n-pendulum --no-default-features`.
+// The `scratch_vs_stack` group sets the reused `Scratch` buffers against the
+// earlier step that built its state and derivative arrays afresh each call.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use n_pendulum::pendulum::{accelerations_impl, ChainParams, DEFAULT_COUPLING, DEFAULT_GRAVITY, MAX_LINKS};
+use n_pendulum::solver::{step_rk4, Scratch};
+use pendulum_core::{rk4_step_slice, Rk4Stages};
+
+/// Substeps per iteration, about one frame's worth at the app's step size.
+const STEPS: u64 = 100;
+
+fn step_chain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("step_rk4");
+    group.throughput(Throughput::Elements(STEPS));
+    for n in 1..=MAX_LINKS {
+        let (lengths, masses) = ([1.0f32; MAX_LINKS], [1.0f32; MAX_LINKS]);
+        let (couplings, dampings) = ([DEFAULT_COUPLING; MAX_LINKS], [0.0f32; MAX_LINKS]);
+        let mut theta: [f32; MAX_LINKS] = std::array::from_fn(|i| 0.6 + 0.1 * i as f32);
+        let mut omega = [0.0f32; MAX_LINKS];
+        let mut scratch = Scratch::default();
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter(|| {
+                for _ in 0..STEPS {
+                    let params = ChainParams { g: DEFAULT_GRAVITY, lengths: &lengths[..n], masses: &masses[..n], couplings: &couplings[..n], dampings: &dampings[..n] };
+                    step_rk4(n, params, &mut theta[..n], &mut omega[..n], black_box(0.001), &mut scratch);
+                }
+                black_box(theta[n - 1])
+            })
+        });
+    }
+    group.finish();
+}
+
+/// The step as it was before `Scratch`: stacked state, stage state and the
+/// per-derivative copies are fresh stack arrays, only `k1..k4` are reused.
+fn step_rk4_stack(n: usize, params: ChainParams, theta: &mut [f32], omega: &mut [f32], dt: f32, k: &mut [[f32; 2 * MAX_LINKS]; 4]) {
+    let [k1, k2, k3, k4] = k;
+    let mut y = [0.0f32; 2 * MAX_LINKS];
+    for i in 0..n { y[2*i] = theta[i]; y[2*i+1] = omega[i]; }
+    let mut tmp = [0.0f32; 2 * MAX_LINKS];
+    rk4_step_slice(&mut y[..2*n], dt, Rk4Stages { k1, k2, k3, k4, tmp: &mut tmp }, |y, out| {
+        let mut thetas = [0.0f32; MAX_LINKS];
+        let mut omegas = [0.0f32; MAX_LINKS];
+        let mut acc = [0.0f32; MAX_LINKS];
+        for i in 0..n { out[2*i] = y[2*i+1]; thetas[i] = y[2*i]; omegas[i] = y[2*i+1]; }
+        accelerations_impl(n, params, &thetas[..n], &omegas[..n], &mut acc[..n]);
+        for i in 0..n { out[2*i+1] = acc[i]; }
+    });
+    for i in 0..n { theta[i] = y[2*i]; omega[i] = y[2*i+1]; }
+}
+
+fn scratch_vs_stack(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scratch_vs_stack");
+    group.throughput(Throughput::Elements(STEPS));
+    let (lengths, masses) = ([1.0f32; MAX_LINKS], [1.0f32; MAX_LINKS]);
+    let (couplings, dampings) = ([DEFAULT_COUPLING; MAX_LINKS], [0.0f32; MAX_LINKS]);
+    for n in [1, 3, MAX_LINKS] {
+        let params = ChainParams { g: DEFAULT_GRAVITY, lengths: &lengths[..n], masses: &masses[..n], couplings: &couplings[..n], dampings: &dampings[..n] };
+        let start: [f32; MAX_LINKS] = std::array::from_fn(|i| 0.6 + 0.1 * i as f32);
+
+        let (mut theta, mut omega) = (start, [0.0f32; MAX_LINKS]);
+        let mut scratch = Scratch::default();
+        group.bench_with_input(BenchmarkId::new("scratch", n), &n, |b, &n| {
+            b.iter(|| {
+                for _ in 0..STEPS {
+                    step_rk4(n, params, &mut theta[..n], &mut omega[..n], black_box(0.001), &mut scratch);
+                }
+                black_box(theta[n - 1])
+            })
+        });
+
+        let (mut theta, mut omega) = (start, [0.0f32; MAX_LINKS]);
+        let mut k = [[0.0f32; 2 * MAX_LINKS]; 4];
+        group.bench_with_input(BenchmarkId::new("stack", n), &n, |b, &n| {
+            b.iter(|| {
+                for _ in 0..STEPS {
+                    step_rk4_stack(n, params, &mut theta[..n], &mut omega[..n], black_box(0.001), &mut k);
+                }
+                black_box(theta[n - 1])
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, step_chain, scratch_vs_stack);
+criterion_main!(benches);
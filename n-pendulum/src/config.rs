@@ -0,0 +1,152 @@
+use std::io;
+use std::path::Path;
+
+use pendulum_core::json::{invalid, load_json, save_json};
+use serde::{Deserialize, Serialize};
+
+use crate::pendulum::{DEFAULT_GRAVITY, LinkParams, MAX_LINKS};
+
+fn default_gravity() -> f32 { DEFAULT_GRAVITY }
+
+/// A whole chain setup as stored in a JSON config file: link count, per-link
+/// parameters and the state each link starts from on reset.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChainConfig {
+    pub n: usize,
+    #[serde(default = "default_gravity")]
+    pub gravity: f32,
+    pub params: Vec<LinkParams>,
+    pub init_theta: Vec<f32>,
+    /// Missing entries start at rest.
+    #[serde(default)]
+    pub init_omega: Vec<f32>,
+}
+
+impl ChainConfig {
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        save_json(self, path)
+    }
+
+    /// Read a config, clamping `n` to `1..=MAX_LINKS` and dropping entries past
+    /// it. Fails if the arrays don't cover `n` links, a length or mass isn't
+    /// positive, or any value isn't finite.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let c: ChainConfig = load_json(path)?;
+        c.validated()
+    }
+
+    fn validated(mut self) -> io::Result<Self> {
+        let c = &mut self;
+        c.n = c.n.clamp(1, MAX_LINKS);
+        if c.params.len() < c.n || c.init_theta.len() < c.n {
+            return Err(invalid(format!("config has {} links but only {} params and {} angles", c.n, c.params.len(), c.init_theta.len())));
+        }
+        c.params.truncate(c.n); c.init_theta.truncate(c.n);
+        c.init_omega.resize(c.n, 0.0);
+        for (i, p) in c.params.iter().enumerate() {
+            if !(p.length > 0.0 && p.mass > 0.0 && p.length.is_finite() && p.mass.is_finite()) {
+                return Err(invalid(format!("link {} needs a positive length and mass", i + 1)));
+            }
+            if !(p.coupling >= 0.0 && p.damping >= 0.0 && p.coupling.is_finite() && p.damping.is_finite()) {
+                return Err(invalid(format!("link {} has a negative or non-finite coupling or damping", i + 1)));
+            }
+        }
+        if !(c.gravity.is_finite() && c.init_theta.iter().chain(&c.init_omega).all(|v| v.is_finite())) {
+            return Err(invalid("gravity and initial state must be finite"));
+        }
+        Ok(self)
+    }
+}
+
+/// A running chain as stored in a JSON state file: its config plus the angles
+/// and angular velocities it had when saved, so the run continues from there.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChainState {
+    #[serde(flatten)]
+    pub config: ChainConfig,
+    pub theta: Vec<f32>,
+    pub omega: Vec<f32>,
+}
+
+impl ChainState {
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        save_json(self, path)
+    }
+
+    /// Read a state file, validating the config as `ChainConfig::load` does.
+    /// Fails if the state doesn't cover every link or isn't finite.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut s: ChainState = load_json(path)?;
+        s.config = s.config.validated()?;
+        let n = s.config.n;
+        if s.theta.len() < n || s.omega.len() < n {
+            return Err(invalid(format!("state has {} links but only {} angles and {} velocities", n, s.theta.len(), s.omega.len())));
+        }
+        s.theta.truncate(n); s.omega.truncate(n);
+        if !s.theta.iter().chain(&s.omega).all(|v| v.is_finite()) {
+            return Err(invalid("state must be finite"));
+        }
+        Ok(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pendulum::DEFAULT_COUPLING;
+    use serde_json::{json, Value};
+
+    /// Load `value` as if it were the state file `name` on disk.
+    fn load_value(name: &str, value: &Value) -> io::Result<ChainState> {
+        let path = std::env::temp_dir().join(format!("{name}-{}.json", std::process::id()));
+        std::fs::write(&path, value.to_string()).unwrap();
+        let s = ChainState::load(&path);
+        let _ = std::fs::remove_file(&path);
+        s
+    }
+
+    /// A state file for `links` identical links with `n` set to `n`.
+    fn state(n: usize, links: usize) -> Value {
+        let link = json!({ "length": 1.0, "mass": 1.0, "coupling": DEFAULT_COUPLING, "damping": 0.0 });
+        json!({
+            "n": n,
+            "params": vec![link; links],
+            "init_theta": vec![0.5; links],
+            "theta": (0..links).map(|i| 0.1 * i as f32).collect::<Vec<_>>(),
+            "omega": vec![0.0; links],
+        })
+    }
+
+    #[test]
+    fn load_clamps_link_count_and_drops_extra_entries() {
+        let s = load_value("chain-too-many", &state(MAX_LINKS + 3, MAX_LINKS + 3)).unwrap();
+        assert_eq!(s.config.n, MAX_LINKS);
+        assert_eq!((s.config.params.len(), s.theta.len(), s.omega.len()), (MAX_LINKS, MAX_LINKS, MAX_LINKS));
+        let s = load_value("chain-zero", &state(0, 2)).unwrap();
+        assert_eq!((s.config.n, s.theta.len()), (1, 1));
+    }
+
+    #[test]
+    fn load_fills_gravity_and_starts_missing_velocities_at_rest() {
+        let s = load_value("chain-defaults", &state(3, 3)).unwrap();
+        assert_eq!(s.config.gravity, DEFAULT_GRAVITY);
+        assert_eq!(s.config.init_omega, vec![0.0; 3]);
+    }
+
+    #[test]
+    fn load_rejects_states_that_dont_cover_the_chain() {
+        let mut short = state(3, 3);
+        short["theta"] = json!([0.1, 0.2]);
+        let mut bad_link = state(3, 3);
+        bad_link["params"][1]["coupling"] = json!(-1.0);
+        let mut massless = state(3, 3);
+        massless["params"][2]["mass"] = json!(0.0);
+        let mut few_params = state(3, 3);
+        few_params["params"] = json!([]);
+        for (name, bad) in [("chain-short", short), ("chain-coupling", bad_link), ("chain-massless", massless), ("chain-params", few_params)] {
+            // it parses, so the load fails on validation rather than syntax
+            assert!(serde_json::from_value::<ChainState>(bad.clone()).is_ok(), "{name}");
+            assert!(load_value(name, &bad).is_err(), "{name}");
+        }
+    }
+}
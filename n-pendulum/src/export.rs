@@ -0,0 +1,45 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use pendulum_core::npy::write_npy_f32;
+
+/// Export the per-link histories as an `N x (1 + links)` array: time, then one theta column per link.
+/// Rows are aligned from the newest sample backwards, so a link with a shorter history only limits `N`.
+pub fn write_history_npy(histories: &[VecDeque<(f32, f32, f32)>], path: &Path) -> io::Result<()> {
+    let rows = histories.iter().map(|h| h.len()).min().unwrap_or(0);
+    let cols = 1 + histories.len();
+    let w = BufWriter::new(File::create(path)?);
+    let values = (0..rows).flat_map(|r| {
+        let t = histories[0][histories[0].len() - rows + r].0;
+        std::iter::once(t).chain(histories.iter().map(move |h| h[h.len() - rows + r].1))
+    });
+    write_npy_f32(w, rows, cols, values)
+}
+
+/// Export the per-link histories as CSV with a header row: `t`, then
+/// `theta_i,omega_i` for every link. Only timestamps that every history still
+/// holds are written, so links whose history started later don't leave gaps.
+pub fn write_history_csv(histories: &[VecDeque<(f32, f32, f32)>], path: &Path) -> io::Result<()> {
+    let mut w = BufWriter::new(File::create(path)?);
+    let mut header = String::from("t");
+    for i in 1..=histories.len() { header.push_str(&format!(",theta_{i},omega_{i}")); }
+    writeln!(w, "{}", header)?;
+    // every history is sampled at the same instants, so the common span is
+    // from the latest first sample to the earliest last sample
+    let start = histories.iter().filter_map(|h| h.front()).map(|s| s.0).fold(f32::NEG_INFINITY, f32::max);
+    let end = histories.iter().filter_map(|h| h.back()).map(|s| s.0).fold(f32::INFINITY, f32::min);
+    if histories.iter().any(|h| h.is_empty()) || start > end { return w.flush(); }
+    let mut rows: Vec<_> = histories.iter().map(|h| h.iter().filter(move |s| s.0 >= start && s.0 <= end)).collect();
+    'rows: loop {
+        let mut line = String::new();
+        for (i, row) in rows.iter_mut().enumerate() {
+            let Some(&(t, theta, omega)) = row.next() else { break 'rows };
+            if i == 0 { line.push_str(&t.to_string()); }
+            line.push_str(&format!(",{},{}", theta, omega));
+        }
+        writeln!(w, "{}", line)?;
+    }
+    w.flush()
+}
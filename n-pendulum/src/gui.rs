@@ -1,59 +1,428 @@
 use eframe::egui;
 use std::time::Instant;
 use std::collections::VecDeque;
+use std::path::Path;
 
-use crate::pendulum::{MAX_LINKS, HISTORY_SECONDS, HISTORY_SAMPLES, LinkParams};
-use crate::solver::step_rk4;
+use n_pendulum::pendulum::{MAX_LINKS, HISTORY_SECONDS, HISTORY_SAMPLES, DEFAULT_COUPLING, DEFAULT_GRAVITY, ChainParams, LinkParams, bob_velocities, chain_energy};
+use n_pendulum::solver::{step_rk4, Scratch};
+use n_pendulum::export::{write_history_csv, write_history_npy};
+use n_pendulum::rng::SplitMix64;
+use n_pendulum::config::{ChainConfig, ChainState};
+
+/// A named chain configuration. `params` and `init_theta` hold one entry per
+/// link; `apply_preset` rejects presets with more than `MAX_LINKS` links.
+#[derive(Clone)]
+pub struct NPreset {
+    pub name: String,
+    pub n: usize,
+    pub params: Vec<LinkParams>,
+    pub init_theta: Vec<f32>,
+}
+
+fn default_presets() -> Vec<NPreset> {
+    let link = |length: f32, mass: f32| LinkParams { length, mass, coupling: DEFAULT_COUPLING, damping: 0.0 };
+    vec![
+        NPreset { name: "Chaotic double".into(), n: 2, params: vec![link(1.0, 1.0), link(1.0, 1.0)], init_theta: vec![120f32.to_radians(), (-20f32).to_radians()] },
+        NPreset {
+            name: "5-link whip".into(), n: 5,
+            params: vec![link(1.0, 2.0), link(0.8, 1.5), link(0.6, 1.0), link(0.45, 0.6), link(0.3, 0.3)],
+            init_theta: vec![90f32.to_radians(), 0.0, 0.0, 0.0, 0.0],
+        },
+        NPreset {
+            name: "Nearly upright".into(), n: 3,
+            params: vec![link(1.0, 1.0); 3],
+            init_theta: vec![179f32.to_radians(), 179.5f32.to_radians(), 180.5f32.to_radians()],
+        },
+    ]
+}
+
+/// Phase-space separation the Lyapunov shadow chain is renormalised back to.
+const LYAPUNOV_D0: f32 = 1e-4;
+/// Seconds between renormalisations of the shadow chain.
+const LYAPUNOV_INTERVAL: f32 = 0.2;
+
+/// Per-link parameters split into the plain arrays the solver takes.
+struct LinkArrays { lengths: [f32; MAX_LINKS], masses: [f32; MAX_LINKS], couplings: [f32; MAX_LINKS], dampings: [f32; MAX_LINKS] }
+
+impl LinkArrays {
+    /// Solver view of the first `n` links under gravity `g`.
+    fn chain(&self, g: f32, n: usize) -> ChainParams<'_> {
+        ChainParams { g, lengths: &self.lengths[..n], masses: &self.masses[..n], couplings: &self.couplings[..n], dampings: &self.dampings[..n] }
+    }
+}
 
 pub struct NPendulumApp {
     pub n: usize,
+    pub gravity: f32,
     pub params: [LinkParams; MAX_LINKS],
     pub theta: [f32; MAX_LINKS],
     pub omega: [f32; MAX_LINKS],
     pub init_theta: [f32; MAX_LINKS],
-    pub histories: [VecDeque<(f32,f32)>; MAX_LINKS],
+    /// Angular velocity (rad/s) each link starts with on reset.
+    pub init_omega: [f32; MAX_LINKS],
+    /// Run a second chain started `twin_epsilon` rad away to show divergence.
+    pub twin_enabled: bool,
+    pub twin_epsilon: f32,
+    pub twin_theta: [f32; MAX_LINKS],
+    pub twin_omega: [f32; MAX_LINKS],
+    // (time, angular distance between the chains)
+    pub divergence: VecDeque<(f32,f32)>,
+    /// Estimate the largest Lyapunov exponent with a hidden shadow chain that is
+    /// pulled back to `LYAPUNOV_D0` away every `LYAPUNOV_INTERVAL` (Benettin's method).
+    pub lyapunov_enabled: bool,
+    lyap_theta: [f32; MAX_LINKS],
+    lyap_omega: [f32; MAX_LINKS],
+    // sum of ln(d / d0) over renormalisations, the time they span, and time since the last one
+    lyap_log_sum: f32,
+    lyap_time: f32,
+    lyap_since: f32,
+    // per link: (time, theta, omega)
+    pub histories: [VecDeque<(f32,f32,f32)>; MAX_LINKS],
+    // (time, total energy), sampled alongside the angle histories
+    pub energy_history: VecDeque<(f32,f32)>,
     pub last_update: Option<Instant>,
     pub start_time: Instant,
-    pub k1: Vec<f32>, k2: Vec<f32>, k3: Vec<f32>, k4: Vec<f32>,
+    /// RK4 buffers shared by the main, twin and shadow chains.
+    pub scratch: Scratch,
     pub draw_points: Vec<egui::Pos2>,
+    /// Canvas zoom in pixels per metre.
+    pub view_scale: f32,
+    /// Canvas pan in pixels, applied to the pivot.
+    pub view_offset: egui::Vec2,
+    // set by the Fit button, handled once the canvas size is known
+    fit_view: bool,
+    // last bob position per frame, in metres relative to the pivot
+    pub trail: VecDeque<egui::Pos2>,
+    pub trail_len: usize,
+    pub show_trail: bool,
+    pub show_com: bool,
+    // centre of mass per frame, in metres relative to the pivot
+    pub com_trail: VecDeque<egui::Pos2>,
+    pub show_com_trail: bool,
+    /// Draw the per-link plots as theta-vs-omega phase portraits instead of time series.
+    pub phase_plots: bool,
+    /// One plot with every link's theta over time instead of a plot per link.
+    pub overlay_links: bool,
+    /// Scale every per-link time series to the range of all links together.
+    pub shared_scale: bool,
+    pub show_velocity: bool,
+    pub velocity_scale: f32,
+    pub highlight_fastest: bool,
+    pub export_path: String,
+    pub export_status: Option<String>,
+    pub config_path: String,
+    pub state_path: String,
+    pub csv_path: String,
+    pub rand_seed: u64,
+    pub rand_length: (f32, f32),
+    pub rand_mass: (f32, f32),
+    pub rand_taper: bool,
+    pub rand_keep_mass: bool,
+    pub initial_energy: f32,
+    pub presets: Vec<NPreset>,
+    pub current_preset: Option<usize>,
 }
 
 impl Default for NPendulumApp {
     fn default() -> Self {
-        let default_param = LinkParams { length: 1.0, mass: 1.0 };
-        NPendulumApp {
+        let default_param = LinkParams { length: 1.0, mass: 1.0, coupling: DEFAULT_COUPLING, damping: 0.0 };
+        let mut app = NPendulumApp {
             n: 3,
+            gravity: DEFAULT_GRAVITY,
             params: [default_param; MAX_LINKS],
-            init_theta: { let mut a = [0.0f32; MAX_LINKS]; a[0]=0.7; a[1]=0.4; a[2]=-0.3; a },
-            theta: { let mut a = [0.0f32; MAX_LINKS]; a[0]=0.7; a[1]=0.4; a[2]=-0.3; a },
+            init_theta: { let mut a = [0.0f32; MAX_LINKS]; a[0]=0.7; a[1]=0.4; a[2] = -0.3; a },
+            theta: { let mut a = [0.0f32; MAX_LINKS]; a[0]=0.7; a[1]=0.4; a[2] = -0.3; a },
             omega: [0.0f32; MAX_LINKS],
+            init_omega: [0.0f32; MAX_LINKS],
+            twin_enabled: false,
+            twin_epsilon: 1e-4,
+            twin_theta: [0.0f32; MAX_LINKS],
+            twin_omega: [0.0f32; MAX_LINKS],
+            divergence: VecDeque::new(),
+            lyapunov_enabled: false,
+            lyap_theta: [0.0f32; MAX_LINKS],
+            lyap_omega: [0.0f32; MAX_LINKS],
+            lyap_log_sum: 0.0,
+            lyap_time: 0.0,
+            lyap_since: 0.0,
             histories: [VecDeque::new(), VecDeque::new(), VecDeque::new(), VecDeque::new(), VecDeque::new(), VecDeque::new(), VecDeque::new()],
+            energy_history: VecDeque::new(),
             last_update: None,
             start_time: Instant::now(),
-            k1: vec![0.0f32; 2*MAX_LINKS], k2: vec![0.0f32; 2*MAX_LINKS], k3: vec![0.0f32; 2*MAX_LINKS], k4: vec![0.0f32; 2*MAX_LINKS],
+            scratch: Scratch::default(),
             draw_points: Vec::with_capacity(MAX_LINKS),
-        }
+            view_scale: 80.0,
+            view_offset: egui::Vec2::ZERO,
+            fit_view: false,
+            trail: VecDeque::with_capacity(600),
+            trail_len: 600,
+            show_trail: true,
+            show_com: false,
+            com_trail: VecDeque::with_capacity(600),
+            show_com_trail: true,
+            phase_plots: false,
+            overlay_links: false,
+            shared_scale: false,
+            show_velocity: false,
+            velocity_scale: 0.25,
+            highlight_fastest: false,
+            export_path: "n_pendulum.npy".into(),
+            export_status: None,
+            config_path: "n_pendulum.json".into(),
+            state_path: "n_pendulum_state.json".into(),
+            csv_path: "n_pendulum.csv".into(),
+            rand_seed: 1,
+            rand_length: (0.5, 1.5),
+            rand_mass: (0.5, 2.0),
+            rand_taper: false,
+            rand_keep_mass: false,
+            initial_energy: 0.0,
+            presets: default_presets(),
+            current_preset: None,
+        };
+        let (k, v) = app.energy(); app.initial_energy = k + v;
+        app
     }
 }
 
 impl NPendulumApp {
     pub fn reset_state(&mut self) {
-        for i in 0..self.n { self.theta[i]=self.init_theta[i]; self.omega[i]=0.0; self.histories[i].clear(); }
-        self.last_update=None; self.start_time=Instant::now();
+        for i in 0..self.n { self.theta[i]=self.init_theta[i]; self.omega[i]=self.init_omega[i]; self.histories[i].clear(); }
+        self.energy_history.clear();
+        self.reseed_twin();
+        self.reseed_lyapunov();
+        self.last_update=None; self.start_time=Instant::now(); self.trail.clear(); self.com_trail.clear();
+        let (k, v) = self.energy(); self.initial_energy = k + v;
+    }
+
+    /// Load preset `idx` and restart from its initial angles. Presets whose link
+    /// count is zero, exceeds `MAX_LINKS` or doesn't match their arrays are ignored.
+    pub fn apply_preset(&mut self, idx: usize) -> bool {
+        let Some(p) = self.presets.get(idx) else { return false };
+        if p.n == 0 || p.n > MAX_LINKS || p.params.len() < p.n || p.init_theta.len() < p.n { return false; }
+        self.n = p.n;
+        for i in 0..p.n {
+            self.params[i] = LinkParams { length: p.params[i].length.max(0.01), mass: p.params[i].mass.max(0.001), coupling: p.params[i].coupling.max(0.0), damping: p.params[i].damping.max(0.0) };
+            self.init_theta[i] = p.init_theta[i];
+            self.init_omega[i] = 0.0;
+        }
+        self.current_preset = Some(idx);
+        self.reset_state();
+        true
+    }
+
+    pub fn config(&self) -> ChainConfig {
+        ChainConfig { n: self.n, gravity: self.gravity, params: self.params[..self.n].to_vec(), init_theta: self.init_theta[..self.n].to_vec(), init_omega: self.init_omega[..self.n].to_vec() }
+    }
+
+    /// Replace the chain with a validated config (see `ChainConfig::load`) and restart it.
+    pub fn apply_config(&mut self, c: &ChainConfig) {
+        self.n = c.n; self.gravity = c.gravity.clamp(0.1, 30.0);
+        for i in 0..c.n { self.params[i] = c.params[i]; self.init_theta[i] = c.init_theta[i]; self.init_omega[i] = c.init_omega[i]; }
+        self.current_preset = None;
+        self.reset_state();
+    }
+
+    pub fn state(&self) -> ChainState {
+        ChainState { config: self.config(), theta: self.theta[..self.n].to_vec(), omega: self.omega[..self.n].to_vec() }
+    }
+
+    /// Apply the state's config, then continue from its saved angles and velocities.
+    pub fn apply_state(&mut self, s: &ChainState) {
+        self.apply_config(&s.config);
+        self.theta[..s.config.n].copy_from_slice(&s.theta);
+        self.omega[..s.config.n].copy_from_slice(&s.omega);
+        self.reseed_twin();
+        self.reseed_lyapunov();
+        let (k, v) = self.energy(); self.initial_energy = k + v;
+    }
+
+    fn link_arrays(&self) -> LinkArrays {
+        let mut a = LinkArrays { lengths: [0.0; MAX_LINKS], masses: [0.0; MAX_LINKS], couplings: [0.0; MAX_LINKS], dampings: [0.0; MAX_LINKS] };
+        for (i, p) in self.params.iter().enumerate() { a.lengths[i] = p.length; a.masses[i] = p.mass; a.couplings[i] = p.coupling; a.dampings[i] = p.damping; }
+        a
+    }
+
+    /// (kinetic, potential) energy of the active links.
+    pub fn energy(&self) -> (f32, f32) {
+        let n = self.n; let a = self.link_arrays();
+        chain_energy(n, self.gravity, &a.lengths[..n], &a.masses[..n], &a.couplings[..n], &self.theta[..n], &self.omega[..n])
+    }
+
+    /// Total kinetic plus potential energy, spring couplings included.
+    pub fn compute_energy(&self) -> f32 { let (k, v) = self.energy(); k + v }
+
+    /// Scale every angular velocity by one common factor so the total energy equals
+    /// `target`, projecting a drifted state back onto the energy surface. Kinetic
+    /// energy is quadratic in omega, so the factor is `sqrt((target - V) / K)`.
+    /// Returns false (and leaves the state alone) when `target` is below the
+    /// current potential energy or the chain is at rest.
+    pub fn rescale_to_energy(&mut self, target: f32) -> bool {
+        let (k, v) = self.energy();
+        if k <= 1e-12 || target < v { return false; }
+        let scale = ((target - v) / k).sqrt();
+        for w in self.omega[..self.n].iter_mut() { *w *= scale; }
+        true
+    }
+
+    /// Restart the twin from the main chain's state with every angle offset by
+    /// `twin_epsilon`, or by one ulp where the angle is too large for ε to register.
+    pub fn reseed_twin(&mut self) {
+        for i in 0..MAX_LINKS {
+            let shifted = self.theta[i] + self.twin_epsilon;
+            self.twin_theta[i] = if shifted == self.theta[i] { self.theta[i].next_up() } else { shifted };
+            self.twin_omega[i] = self.omega[i];
+        }
+        self.divergence.clear();
+    }
+
+    /// Euclidean distance between the two chains' angles, each difference wrapped into [-π, π).
+    pub fn twin_distance(&self) -> f32 {
+        let wrap = |d: f32| (d + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+        (0..self.n).map(|i| wrap(self.twin_theta[i] - self.theta[i]).powi(2)).sum::<f32>().sqrt()
+    }
+
+    pub fn step_twin(&mut self, dt: f32) {
+        let n = self.n;
+        let a = self.link_arrays();
+        step_rk4(n, a.chain(self.gravity, n), &mut self.twin_theta[..n], &mut self.twin_omega[..n], dt, &mut self.scratch);
+    }
+
+    /// Restart the shadow chain `LYAPUNOV_D0` away in angle and zero the accumulator.
+    pub fn reseed_lyapunov(&mut self) {
+        let offset = LYAPUNOV_D0 / (self.n.max(1) as f32).sqrt();
+        for i in 0..MAX_LINKS { self.lyap_theta[i] = self.theta[i] + offset; self.lyap_omega[i] = self.omega[i]; }
+        self.lyap_log_sum = 0.0; self.lyap_time = 0.0; self.lyap_since = 0.0;
+    }
+
+    /// Running estimate of the largest Lyapunov exponent in 1/s, once one interval has passed.
+    pub fn lyapunov_estimate(&self) -> Option<f32> {
+        (self.lyap_time > 0.0).then(|| self.lyap_log_sum / self.lyap_time)
+    }
+
+    /// Advance the shadow chain and, every `LYAPUNOV_INTERVAL`, log its growth
+    /// and scale its offset from the main chain back to `LYAPUNOV_D0`.
+    pub fn step_lyapunov(&mut self, dt: f32) {
+        let n = self.n;
+        let a = self.link_arrays();
+        step_rk4(n, a.chain(self.gravity, n), &mut self.lyap_theta[..n], &mut self.lyap_omega[..n], dt, &mut self.scratch);
+        self.lyap_since += dt;
+        if self.lyap_since < LYAPUNOV_INTERVAL { return; }
+        let d = (0..n).map(|i| (self.lyap_theta[i] - self.theta[i]).powi(2) + (self.lyap_omega[i] - self.omega[i]).powi(2)).sum::<f32>().sqrt();
+        if d > 0.0 && d.is_finite() {
+            self.lyap_log_sum += (d / LYAPUNOV_D0).ln();
+            self.lyap_time += self.lyap_since;
+            let k = LYAPUNOV_D0 / d;
+            for i in 0..n {
+                self.lyap_theta[i] = self.theta[i] + (self.lyap_theta[i] - self.theta[i]) * k;
+                self.lyap_omega[i] = self.omega[i] + (self.lyap_omega[i] - self.omega[i]) * k;
+            }
+        } else {
+            self.reseed_lyapunov();
+        }
+        self.lyap_since = 0.0;
     }
 
     pub fn step_rk4(&mut self, dt: f32) {
         let n = self.n;
-        let mut lengths = [0.0f32; MAX_LINKS];
-        let mut masses = [0.0f32; MAX_LINKS];
-        for i in 0..n { lengths[i] = self.params[i].length; masses[i] = self.params[i].mass; }
-        step_rk4(n, &lengths[..n], &masses[..n], &mut self.theta[..n], &mut self.omega[..n], dt, &mut self.k1, &mut self.k2, &mut self.k3, &mut self.k4);
+        let a = self.link_arrays();
+        step_rk4(n, a.chain(self.gravity, n), &mut self.theta[..n], &mut self.omega[..n], dt, &mut self.scratch);
+    }
+
+    /// Draw each active link's length and mass uniformly from the given ranges.
+    /// The same seed always produces the same chain. With `taper` the lengths
+    /// are sorted so they decrease down the chain.
+    pub fn randomize_params(&mut self, seed: u64, length_range: (f32, f32), mass_range: (f32, f32), taper: bool) {
+        let mut rng = SplitMix64::new(seed);
+        for p in self.params[..self.n].iter_mut() {
+            p.length = rng.range(length_range.0, length_range.1).max(0.01);
+            p.mass = rng.range(mass_range.0, mass_range.1).max(0.001);
+        }
+        if taper { self.params[..self.n].sort_by(|a, b| b.length.total_cmp(&a.length)); }
+    }
+
+    pub fn total_mass(&self) -> f32 { self.params[..self.n].iter().map(|p| p.mass).sum() }
+
+    /// Rescale the active links' masses so they sum to `total`, keeping their ratios.
+    pub fn normalize_total_mass(&mut self, total: f32) {
+        let current = self.total_mass();
+        if current > 0.0 { for p in self.params[..self.n].iter_mut() { p.mass = (p.mass * total / current).max(0.001); } }
+    }
+
+    pub fn bob_velocities(&self) -> [(f32, f32); MAX_LINKS] {
+        let mut lengths = [0.0f32; MAX_LINKS]; let mut vel = [(0.0f32, 0.0f32); MAX_LINKS];
+        for (l, p) in lengths.iter_mut().zip(self.params.iter()) { *l = p.length; }
+        bob_velocities(self.n, &lengths[..self.n], &self.theta[..self.n], &self.omega[..self.n], &mut vel[..self.n]);
+        vel
+    }
+
+    fn link_plot_title(&self, idx: usize) -> String {
+        if self.phase_plots { format!("Link #{} (θ vs ω)", idx+1) } else { format!("Link #{}", idx+1) }
+    }
+
+    fn link_color(idx: usize) -> egui::Color32 { egui::Color32::from_rgb(120, 200 - (idx as u8 * 30), 150) }
+
+    /// Plot of link `idx`; time series use `shared` as their theta range when given.
+    fn draw_link_plot(&self, painter: &egui::Painter, rect: egui::Rect, idx: usize, shared: Option<(f32, f32)>) {
+        let color = Self::link_color(idx); let h = &self.histories[idx];
+        if self.phase_plots { draw_phase_reuse(painter, rect, h, color); }
+        else if let (Some(range), Some(front), Some(back)) = (shared, h.front(), h.back()) {
+            draw_series_in(painter, rect, h, |&(t, th, _)| (t, th), color, (front.0, back.0), range);
+            painter.rect_stroke(rect, 0.0, (1.0, egui::Color32::from_gray(80)));
+        }
+        else { draw_series_reuse(painter, rect, h, |&(t, th, _)| (t, th), color); }
+    }
+
+    /// (min, max) theta over every active link's history, or `None` while
+    /// there is nothing to plot.
+    fn shared_theta_range(&self) -> Option<(f32, f32)> {
+        let (mut minv, mut maxv) = (f32::INFINITY, f32::NEG_INFINITY);
+        for h in self.histories[..self.n].iter().filter(|h| h.len() >= 2) {
+            for &(_, th, _) in h { minv = minv.min(th); maxv = maxv.max(th); }
+        }
+        if !minv.is_finite() { return None; }
+        if (maxv-minv).abs()<1e-6 { maxv = minv+1.0; }
+        Some((minv, maxv))
+    }
+
+    /// Every active link's theta over time in one plot, on a time and value
+    /// range shared by all of them, with a colour legend.
+    fn draw_overlay_plot(&self, painter: &egui::Painter, rect: egui::Rect) {
+        let histories = &self.histories[..self.n];
+        let (mut t0, mut t1) = (f32::INFINITY, f32::NEG_INFINITY);
+        for h in histories.iter().filter(|h| h.len() >= 2) { t0 = t0.min(h.front().unwrap().0); t1 = t1.max(h.back().unwrap().0); }
+        if let Some(range) = self.shared_theta_range() {
+            for (i, h) in histories.iter().enumerate() { draw_series_in(painter, rect, h, |&(t, th, _)| (t, th), Self::link_color(i), (t0, t1), range); }
+        }
+        let font = egui::FontId::proportional(11.0);
+        for i in 0..self.n {
+            let y = rect.top() + 10.0 + i as f32 * 14.0;
+            painter.line_segment([egui::pos2(rect.right() - 62.0, y), egui::pos2(rect.right() - 48.0, y)], (2.5, Self::link_color(i)));
+            painter.text(egui::pos2(rect.right() - 42.0, y), egui::Align2::LEFT_CENTER, format!("#{}", i+1), font.clone(), egui::Color32::from_gray(200));
+        }
+        painter.rect_stroke(rect, 0.0, (1.0, egui::Color32::from_gray(80)));
+    }
+
+    /// Mass-weighted mean of the bob positions in `draw_points`, or `None` before the first frame.
+    pub fn center_of_mass(&self) -> Option<egui::Pos2> {
+        let total = self.total_mass();
+        if self.draw_points.len() < self.n || total <= 0.0 { return None; }
+        let sum = self.draw_points.iter().zip(&self.params[..self.n]).fold(egui::Vec2::ZERO, |acc, (p, l)| acc + p.to_vec2() * l.mass);
+        Some((sum / total).to_pos2())
     }
 
     pub fn push_histories(&mut self) {
         let t = self.start_time.elapsed().as_secs_f32();
         for i in 0..self.n {
-            let h = &mut self.histories[i]; h.push_back((t, self.theta[i]));
+            let h = &mut self.histories[i]; h.push_back((t, self.theta[i], self.omega[i]));
+            while let Some(&(old,_,_)) = h.front() { if t-old>HISTORY_SECONDS || h.len()>HISTORY_SAMPLES { h.pop_front(); } else { break; } }
+        }
+        let e = self.compute_energy();
+        let h = &mut self.energy_history; h.push_back((t, e));
+        while let Some(&(old,_)) = h.front() { if t-old>HISTORY_SECONDS || h.len()>HISTORY_SAMPLES { h.pop_front(); } else { break; } }
+        if self.twin_enabled {
+            let d = self.twin_distance();
+            let h = &mut self.divergence; h.push_back((t, d));
             while let Some(&(old,_)) = h.front() { if t-old>HISTORY_SECONDS || h.len()>HISTORY_SAMPLES { h.pop_front(); } else { break; } }
         }
     }
@@ -62,7 +431,18 @@ impl NPendulumApp {
 impl eframe::App for NPendulumApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
-            ui.horizontal_centered(|ui| { ui.heading("N-Pendulum Simulator"); });
+            ui.horizontal_centered(|ui| {
+                ui.heading("N-Pendulum Simulator");
+                ui.separator();
+                ui.label(format!("E = {:.3} J", self.compute_energy()));
+                if self.lyapunov_enabled {
+                    ui.separator();
+                    match self.lyapunov_estimate() {
+                        Some(l) => ui.label(format!("λ ≈ {:.3} /s ({:.0} s)", l, self.lyap_time)),
+                        None => ui.label("λ: measuring…"),
+                    };
+                }
+            });
         });
 
         // Controls window (compact)
@@ -76,33 +456,181 @@ impl eframe::App for NPendulumApp {
                 ui.heading("Controls");
                 ui.separator();
                 ui.horizontal(|ui| {
-                    ui.label("Links:"); let mut n = self.n as i32; ui.add(egui::Slider::new(&mut n, 1..=MAX_LINKS as i32).show_value(true)); self.n = n as usize;
+                    ui.label("Links:"); let mut n = self.n as i32; ui.add(egui::Slider::new(&mut n, 1..=MAX_LINKS as i32).show_value(true));
+                    // the shadow chain's new links and the running sum belong to the old chain
+                    if n as usize != self.n { self.n = n as usize; self.reseed_lyapunov(); }
                     if ui.button("Reset").clicked() { self.reset_state(); }
+                    if ui.button("Fit").on_hover_text("Zoom so the fully extended chain fits the canvas").clicked() { self.fit_view = true; }
                 });
+                ui.horizontal(|ui| {
+                    ui.label("Gravity:");
+                    ui.add(egui::Slider::new(&mut self.gravity, 0.1..=30.0).suffix(" m/s²"));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Preset:");
+                    let selected = self.current_preset.and_then(|i| self.presets.get(i)).map_or("Custom", |p| p.name.as_str()).to_owned();
+                    let mut to_apply = None;
+                    egui::ComboBox::from_id_source("n_preset").selected_text(selected).show_ui(ui, |ui| {
+                        for (i, p) in self.presets.iter().enumerate() {
+                            if ui.selectable_label(self.current_preset == Some(i), &p.name).clicked() { to_apply = Some(i); }
+                        }
+                    });
+                    if let Some(i) = to_apply { self.apply_preset(i); }
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.show_velocity, "Velocity vectors");
+                    ui.add_enabled(self.show_velocity, egui::Slider::new(&mut self.velocity_scale, 0.05..=1.0).text("scale (s)"));
+                });
+                ui.checkbox(&mut self.highlight_fastest, "Highlight fastest link");
+                ui.checkbox(&mut self.phase_plots, "Phase portraits (θ vs ω)");
+                ui.checkbox(&mut self.shared_scale, "Shared scale").on_hover_text("Give every link plot the same θ range so amplitudes compare directly");
+                ui.checkbox(&mut self.overlay_links, "Overlay all links").on_hover_text("One θ-vs-time plot for the whole chain, on a shared scale");
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.show_trail, "Show trail");
+                    ui.add_enabled(self.show_trail, egui::Slider::new(&mut self.trail_len, 10..=3000).text("points").logarithmic(true));
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.show_com, "Centre of mass");
+                    ui.add_enabled(self.show_com, egui::Checkbox::new(&mut self.show_com_trail, "trace"));
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.export_path).desired_width(160.0));
+                    if ui.button("Export .npy").clicked() {
+                        self.export_status = Some(match write_history_npy(&self.histories[..self.n], Path::new(&self.export_path)) {
+                            Ok(()) => format!("Wrote {} links", self.n),
+                            Err(e) => format!("Export failed: {}", e),
+                        });
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.csv_path).desired_width(160.0));
+                    if ui.button("Export CSV").on_hover_text("Time, then theta and omega for each link").clicked() {
+                        self.export_status = Some(match write_history_csv(&self.histories[..self.n], Path::new(&self.csv_path)) {
+                            Ok(()) => format!("Wrote {} links", self.n),
+                            Err(e) => format!("Export failed: {}", e),
+                        });
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.config_path).desired_width(120.0));
+                    if ui.button("Save Config").clicked() {
+                        self.export_status = Some(match self.config().save(Path::new(&self.config_path)) {
+                            Ok(()) => format!("Saved {} links", self.n),
+                            Err(e) => format!("Save failed: {}", e),
+                        });
+                    }
+                    if ui.button("Load Config").clicked() {
+                        self.export_status = Some(match ChainConfig::load(Path::new(&self.config_path)) {
+                            Ok(c) => { self.apply_config(&c); format!("Loaded {} links", c.n) }
+                            Err(e) => format!("Load failed: {}", e),
+                        });
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.state_path).desired_width(120.0));
+                    if ui.button("Save State").on_hover_text("Config plus the current angles and velocities").clicked() {
+                        self.export_status = Some(match self.state().save(Path::new(&self.state_path)) {
+                            Ok(()) => format!("Saved state of {} links", self.n),
+                            Err(e) => format!("Save failed: {}", e),
+                        });
+                    }
+                    if ui.button("Load State").clicked() {
+                        self.export_status = Some(match ChainState::load(Path::new(&self.state_path)) {
+                            Ok(s) => { self.apply_state(&s); format!("Loaded state of {} links", s.config.n) }
+                            Err(e) => format!("Load failed: {}", e),
+                        });
+                    }
+                });
+                if let Some(status) = &self.export_status { ui.small(status); }
+                ui.horizontal(|ui| {
+                    ui.label(format!("E: {:.3} (E0 {:.3})", self.compute_energy(), self.initial_energy));
+                    if ui.button("Restore energy").on_hover_text("Rescale velocities back to the energy at reset").clicked() {
+                        self.rescale_to_energy(self.initial_energy);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.checkbox(&mut self.twin_enabled, "Twin chain").on_hover_text("Second chain started a tiny angle away").changed() { self.reseed_twin(); }
+                    // the separation shown belongs to the ε the twin was started with
+                    if ui.add_enabled(self.twin_enabled, egui::Slider::new(&mut self.twin_epsilon, 1e-6..=1e-1).logarithmic(true).text("ε (rad)")).changed() { self.reseed_twin(); }
+                });
+                if ui.checkbox(&mut self.lyapunov_enabled, "Lyapunov estimate").on_hover_text("Largest Lyapunov exponent, shown in the top bar").changed() { self.reseed_lyapunov(); }
+                if self.twin_enabled {
+                    ui.label("Separation, log₁₀ |Δθ|:");
+                    let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width().min(300.0), 70.0), egui::Sense::hover());
+                    draw_series_reuse(&ui.painter_at(rect), rect, &self.divergence, |&(t, d)| (t, d.max(1e-12).log10()), egui::Color32::from_rgb(255, 150, 60));
+                }
+                ui.label("Energy vs time:");
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width().min(300.0), 70.0), egui::Sense::hover());
+                draw_series_reuse(&ui.painter_at(rect), rect, &self.energy_history, |&s| s, egui::Color32::from_rgb(240, 190, 90));
                 ui.add_space(4.0);
                 let max_panel_h = 420.0f32.min(ui.available_height());
                 egui::ScrollArea::vertical().max_height(max_panel_h).show(ui, |ui| {
+                    ui.collapsing("Randomize", |ui| {
+                        ui.horizontal(|ui| { ui.label("Seed:"); ui.add(egui::DragValue::new(&mut self.rand_seed)); });
+                        ui.horizontal(|ui| {
+                            ui.label("Length:");
+                            ui.add(egui::DragValue::new(&mut self.rand_length.0).speed(0.05).clamp_range(0.05..=10.0));
+                            ui.label("to");
+                            ui.add(egui::DragValue::new(&mut self.rand_length.1).speed(0.05).clamp_range(0.05..=10.0));
+                            ui.label("m");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Mass:");
+                            ui.add(egui::DragValue::new(&mut self.rand_mass.0).speed(0.05).clamp_range(0.01..=20.0));
+                            ui.label("to");
+                            ui.add(egui::DragValue::new(&mut self.rand_mass.1).speed(0.05).clamp_range(0.01..=20.0));
+                            ui.label("kg");
+                        });
+                        ui.checkbox(&mut self.rand_taper, "Tapered (decreasing lengths)");
+                        ui.checkbox(&mut self.rand_keep_mass, "Keep total mass");
+                        if ui.button("Randomize").clicked() {
+                            let total = self.total_mass();
+                            self.randomize_params(self.rand_seed, self.rand_length, self.rand_mass, self.rand_taper);
+                            if self.rand_keep_mass { self.normalize_total_mass(total); }
+                            self.reset_state();
+                        }
+                    });
                     ui.collapsing("Per-link settings", |ui| {
                         for i in 0..self.n {
                             ui.collapsing(format!("Link #{}", i+1), |ui| {
                                 ui.horizontal(|ui| {
                                     ui.label("Length:");
-                                    let mut len = self.params[i].length as f32;
+                                    let mut len = self.params[i].length;
                                     if ui.add(egui::DragValue::new(&mut len).speed(0.1)).changed() { self.params[i].length = len.max(0.01); }
                                     ui.label("m");
                                 });
                                 ui.horizontal(|ui| {
                                     ui.label("Mass:");
-                                    let mut mass = self.params[i].mass as f32;
+                                    let mut mass = self.params[i].mass;
                                     if ui.add(egui::DragValue::new(&mut mass).speed(0.1)).changed() { self.params[i].mass = mass.max(0.001); }
                                     ui.label("kg");
                                 });
+                                ui.horizontal(|ui| {
+                                    ui.label("Damping:");
+                                    let mut c = self.params[i].damping;
+                                    if ui.add(egui::DragValue::new(&mut c).speed(0.01)).changed() { self.params[i].damping = c.max(0.0); }
+                                    ui.label("N·m·s/rad");
+                                });
+                                if i > 0 {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Coupling:");
+                                        let mut k = self.params[i].coupling;
+                                        if ui.add(egui::DragValue::new(&mut k).speed(0.1)).on_hover_text("Joint spring to the link above").changed() { self.params[i].coupling = k.max(0.0); }
+                                        ui.label("N·m/rad");
+                                    });
+                                }
                                 ui.horizontal(|ui| {
                                     ui.label("Initial angle:");
                                     let mut deg = self.init_theta[i].to_degrees();
                                     if ui.add(egui::DragValue::new(&mut deg).speed(1.0)).changed() { self.init_theta[i] = deg.to_radians(); }
                                     ui.label("deg");
                                 });
+                                ui.horizontal(|ui| {
+                                    ui.label("Initial ω:");
+                                    let mut deg = self.init_omega[i].to_degrees();
+                                    if ui.add(egui::DragValue::new(&mut deg).speed(5.0)).on_hover_text("Launch speed, applied on reset").changed() { self.init_omega[i] = deg.to_radians(); }
+                                    ui.label("deg/s");
+                                });
                             });
                         }
                     });
@@ -112,26 +640,124 @@ impl eframe::App for NPendulumApp {
         // timing & integration
         let now = Instant::now(); let dt = if let Some(last)=self.last_update { now.duration_since(last).as_secs_f32() } else { 0.0 };
         self.last_update = Some(now);
-        let dt = dt.min(0.05).max(0.0);
-        if dt>0.0 { let steps = ((dt/0.005).ceil() as usize).max(1); let sub = dt/steps as f32; for _ in 0..steps { self.step_rk4(sub); self.push_histories(); } }
+        let dt = dt.clamp(0.0, 0.05);
+        if dt>0.0 {
+            let steps = ((dt/0.005).ceil() as usize).max(1); let sub = dt/steps as f32;
+            for _ in 0..steps {
+                self.step_rk4(sub);
+                if self.twin_enabled { self.step_twin(sub); }
+                if self.lyapunov_enabled { self.step_lyapunov(sub); }
+                self.push_histories();
+            }
+        }
 
         egui::CentralPanel::default().show(ctx, |ui| {
             let available = ui.available_size();
             let canvas_fraction = 0.72f32; let min_canvas_h = 220.0f32;
             let canvas_height = (available.y * canvas_fraction).clamp(min_canvas_h, available.y - 80.0);
             let canvas = egui::Vec2::new(available.x, canvas_height);
-            let (rect, _) = ui.allocate_exact_size(canvas, egui::Sense::hover());
+            let (rect, resp) = ui.allocate_exact_size(canvas, egui::Sense::drag());
             let painter = ui.painter_at(rect);
-            let center = rect.center(); let mut x = center.x; let mut y = center.y - 20.0;
+            if self.fit_view {
+                // the extended chain can point anywhere within a circle of its total length
+                let reach: f32 = self.params[..self.n].iter().map(|p| p.length).sum();
+                self.view_scale = (0.45 * rect.width().min(rect.height()) / reach.max(0.01)).clamp(5.0, 2000.0);
+                self.view_offset = egui::vec2(0.0, 20.0);
+                self.fit_view = false;
+            }
+            self.view_offset += resp.drag_delta();
+            if let Some(hover) = resp.hover_pos() {
+                let scroll = ui.input(|i| i.scroll_delta.y);
+                if scroll != 0.0 {
+                    // zoom about the pointer: keep the point under it fixed
+                    let pivot = rect.center() + egui::vec2(0.0, -20.0) + self.view_offset;
+                    let factor = (scroll * 0.002).exp();
+                    let new_scale = (self.view_scale * factor).clamp(5.0, 2000.0);
+                    self.view_offset += (hover - pivot) * (1.0 - new_scale / self.view_scale);
+                    self.view_scale = new_scale;
+                }
+            }
+            let scale = self.view_scale;
+            let center = rect.center() + self.view_offset; let mut x = center.x; let mut y = center.y - 20.0;
             self.draw_points.clear();
-            for i in 0..self.n { let l = self.params[i].length*80.0; let ang = self.theta[i]; let nx = x + l*ang.sin(); let ny = y + l*ang.cos(); self.draw_points.push(egui::pos2(nx,ny)); x=nx; y=ny; }
-            let mut prev = egui::pos2(center.x, center.y-20.0);
+            for i in 0..self.n { let l = self.params[i].length*scale; let ang = self.theta[i]; let nx = x + l*ang.sin(); let ny = y + l*ang.cos(); self.draw_points.push(egui::pos2(nx,ny)); x=nx; y=ny; }
+            let pivot = egui::pos2(center.x, center.y-20.0);
+            if let Some(&last) = self.draw_points.last() {
+                self.trail.push_back(((last - pivot) / scale).to_pos2());
+                while self.trail.len() > self.trail_len { self.trail.pop_front(); }
+            }
+            if self.show_trail && self.trail.len() > 1 {
+                // oldest segments fade towards transparent
+                let count = self.trail.len() as f32;
+                for (i, (a, b)) in self.trail.iter().zip(self.trail.iter().skip(1)).enumerate() {
+                    let alpha = (255.0 * (i as f32 + 1.0) / count) as u8;
+                    painter.line_segment([pivot + a.to_vec2()*scale, pivot + b.to_vec2()*scale], (1.5, egui::Color32::from_rgba_unmultiplied(120, 200, 255, alpha)));
+                }
+            }
+            let com = self.center_of_mass();
+            if let Some(c) = com {
+                self.com_trail.push_back(((c - pivot) / scale).to_pos2());
+                while self.com_trail.len() > self.trail_len { self.com_trail.pop_front(); }
+            }
+            let com_color = egui::Color32::from_rgb(255, 120, 220);
+            if self.show_com && self.show_com_trail && self.com_trail.len() > 1 {
+                let count = self.com_trail.len() as f32;
+                for (i, (a, b)) in self.com_trail.iter().zip(self.com_trail.iter().skip(1)).enumerate() {
+                    let alpha = (i as f32 + 1.0) / count;
+                    painter.line_segment([pivot + a.to_vec2()*scale, pivot + b.to_vec2()*scale], (1.5, com_color.gamma_multiply(alpha)));
+                }
+            }
+            if self.twin_enabled {
+                let twin = egui::Color32::from_rgba_unmultiplied(255, 150, 60, 180);
+                let mut prev = pivot;
+                for i in 0..self.n {
+                    let ang = self.twin_theta[i];
+                    let p = prev + egui::vec2(ang.sin(), ang.cos()) * self.params[i].length * scale;
+                    painter.line_segment([prev, p], (2.0, twin)); painter.circle_filled(p, 5.0, twin); prev = p;
+                }
+            }
+            let mut prev = pivot;
             for (i,p) in self.draw_points.iter().enumerate() { painter.line_segment([prev,*p], (2.0, egui::Color32::WHITE)); painter.circle_filled(*p,6.0, egui::Color32::from_rgb(200,100 + (i as u8*20),100)); prev = *p; }
+            if let (true, Some(c)) = (self.show_com, com) {
+                // crossed circle, the usual centre-of-mass symbol
+                let r = 7.0;
+                painter.circle_stroke(c, r, (2.0, com_color));
+                painter.line_segment([c - egui::vec2(r, 0.0), c + egui::vec2(r, 0.0)], (1.5, com_color));
+                painter.line_segment([c - egui::vec2(0.0, r), c + egui::vec2(0.0, r)], (1.5, com_color));
+            }
+            if self.highlight_fastest && !self.draw_points.is_empty() {
+                let vel = self.bob_velocities();
+                let fastest = (0..self.n).max_by(|&a, &b| (vel[a].0.hypot(vel[a].1)).total_cmp(&vel[b].0.hypot(vel[b].1))).unwrap_or(0);
+                let start = if fastest == 0 { egui::pos2(center.x, center.y-20.0) } else { self.draw_points[fastest-1] };
+                let glow = egui::Color32::from_rgb(255,230,90);
+                painter.line_segment([start, self.draw_points[fastest]], (4.0, glow));
+                painter.circle_stroke(self.draw_points[fastest], 10.0, (2.5, glow));
+            }
+            if self.show_velocity {
+                // arrow length = distance travelled in `velocity_scale` seconds at the current speed
+                let vel = self.bob_velocities();
+                for (p,&(vx,vy)) in self.draw_points.iter().zip(vel.iter()) { painter.arrow(*p, egui::vec2(vx,vy)*scale*self.velocity_scale, egui::Stroke::new(1.5, egui::Color32::from_rgb(255,210,80))); }
+            }
 
             // plots grid
             let ideal_plot_w = 240.0f32; let gap = 8.0f32;
+            let shared = if self.shared_scale { self.shared_theta_range() } else { None };
+            if self.overlay_links {
+                let plot_h = (available.y - canvas_height - 12.0 - 4.0 * gap).clamp(70.0, 320.0);
+                ui.horizontal(|ui| {
+                    ui.add_space(gap);
+                    ui.group(|ui| {
+                        ui.vertical(|ui| {
+                            ui.label("All links (θ vs time)");
+                            let (rect, _) = ui.allocate_exact_size(egui::Vec2::new(available.x - 4.0 * gap, plot_h), egui::Sense::hover());
+                            self.draw_overlay_plot(&ui.painter_at(rect), rect);
+                        });
+                    });
+                });
+                return;
+            }
             let mut cols = (available.x / (ideal_plot_w + gap)).floor() as usize; if cols == 0 { cols = 1; }
-            cols = cols.min(self.n.max(1)); let rows = (self.n + cols - 1) / cols;
+            cols = cols.min(self.n.max(1)); let rows = self.n.div_ceil(cols);
             let plot_w = (available.x - gap * (cols as f32 + 1.0)) / cols as f32;
             let remaining_h = (available.y - canvas_height - 12.0).max(0.0);
             let mut plot_h = if rows > 0 { (remaining_h - gap * (rows as f32 + 1.0)) / rows as f32 } else { 120.0 };
@@ -149,9 +775,9 @@ impl eframe::App for NPendulumApp {
                             let idx = r * cols + c;
                             ui.group(|ui| {
                                 ui.vertical(|ui| {
-                                    ui.horizontal(|ui| { if idx < self.n { ui.label(format!("Link #{}", idx+1)); } else { ui.label(""); } });
+                                    ui.horizontal(|ui| { if idx < self.n { ui.label(self.link_plot_title(idx)); } else { ui.label(""); } });
                                     let (rect, _) = ui.allocate_exact_size(egui::Vec2::new(plot_w, plot_h), egui::Sense::hover());
-                                    if idx < self.n { draw_series_reuse(&ui.painter_at(rect), rect, &self.histories[idx], egui::Color32::from_rgb(120, 200 - (idx as u8 * 30), 150)); }
+                                    if idx < self.n { self.draw_link_plot(&ui.painter_at(rect), rect, idx, shared); }
                                 });
                             });
                         }
@@ -169,9 +795,9 @@ impl eframe::App for NPendulumApp {
                                 let idx = r * cols + c;
                                 ui.group(|ui| {
                                     ui.vertical(|ui| {
-                                        ui.horizontal(|ui| { if idx < self.n { ui.label(format!("Link #{}", idx+1)); } else { ui.label(""); } });
+                                        ui.horizontal(|ui| { if idx < self.n { ui.label(self.link_plot_title(idx)); } else { ui.label(""); } });
                                         let (rect, _) = ui.allocate_exact_size(egui::Vec2::new(plot_w, plot_h), egui::Sense::hover());
-                                        if idx < self.n { draw_series_reuse(&ui.painter_at(rect), rect, &self.histories[idx], egui::Color32::from_rgb(120, 200 - (idx as u8 * 30), 150)); }
+                                        if idx < self.n { self.draw_link_plot(&ui.painter_at(rect), rect, idx, shared); }
                                     });
                                 });
                             }
@@ -187,12 +813,56 @@ impl eframe::App for NPendulumApp {
 }
 
 // leave draw helper in main module so both gui.rs and tests can call it easily
-pub fn draw_series_reuse(painter: &egui::Painter, rect: egui::Rect, series: &VecDeque<(f32,f32)>, color: egui::Color32) {
-    use egui::pos2;
+// `sample` maps each entry to its (time, value) pair. The trace is one polyline
+// with at most one point per pixel column, so dense histories stay cheap.
+pub fn draw_series_reuse<T>(painter: &egui::Painter, rect: egui::Rect, series: &VecDeque<T>, sample: impl Fn(&T) -> (f32,f32), color: egui::Color32) {
     if series.len()<2 { painter.rect_stroke(rect, 0.0, (1.0, egui::Color32::from_gray(80))); return; }
-    let t0 = series.front().unwrap().0; let t1 = series.back().unwrap().0; let dt = (t1-t0).max(1e-6);
-    let mut minv=f32::INFINITY; let mut maxv=f32::NEG_INFINITY; for &(_,v) in series.iter() { minv=minv.min(v); maxv=maxv.max(v); }
+    let t0 = sample(series.front().unwrap()).0; let t1 = sample(series.back().unwrap()).0;
+    let mut minv=f32::INFINITY; let mut maxv=f32::NEG_INFINITY; for (_,v) in series.iter().map(&sample) { minv=minv.min(v); maxv=maxv.max(v); }
     if (maxv-minv).abs()<1e-6 { maxv = minv+1.0; }
-    let mut prev: Option<egui::Pos2> = None; for &(t,v) in series.iter() { let x = rect.left() + ((t-t0)/dt)*rect.width(); let y = rect.bottom() - ((v-minv)/(maxv-minv))*rect.height(); let p = pos2(x,y); if let Some(p0)=prev { painter.line_segment([p0,p], (1.5,color)); } prev = Some(p); }
+    draw_series_in(painter, rect, series, sample, color, (t0, t1), (minv, maxv));
     painter.rect_stroke(rect, 0.0, (1.0, egui::Color32::from_gray(80)));
 }
+
+/// The polyline part of `draw_series_reuse`, mapping `t_range` across and
+/// `v_range` up `rect`, so several series can share one scale.
+fn draw_series_in<T>(painter: &egui::Painter, rect: egui::Rect, series: &VecDeque<T>, sample: impl Fn(&T) -> (f32,f32), color: egui::Color32, (t0, t1): (f32, f32), (minv, maxv): (f32, f32)) {
+    use egui::pos2;
+    if series.len()<2 { return; }
+    let dt = (t1-t0).max(1e-6);
+    let mut points: Vec<egui::Pos2> = Vec::with_capacity(series.len().min(rect.width() as usize + 2));
+    let (mut last_column, last) = (i64::MIN, series.len() - 1);
+    for (i, (t,v)) in series.iter().map(&sample).enumerate() {
+        let x = rect.left() + ((t-t0)/dt)*rect.width(); let y = rect.bottom() - ((v-minv)/(maxv-minv))*rect.height();
+        // keep the first sample in each pixel column, but always end on the newest one
+        let column = x.floor() as i64;
+        if column == last_column { if i == last { *points.last_mut().unwrap() = pos2(x,y); } continue; }
+        last_column = column; points.push(pos2(x,y));
+    }
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+}
+
+/// Phase portrait of a (time, theta, omega) history: theta on x, omega on y,
+/// each axis scaled to the data. Older samples are drawn fainter.
+pub fn draw_phase_reuse(painter: &egui::Painter, rect: egui::Rect, series: &VecDeque<(f32,f32,f32)>, color: egui::Color32) {
+    use egui::pos2;
+    painter.rect_stroke(rect, 0.0, (1.0, egui::Color32::from_gray(80)));
+    if series.len()<2 { return; }
+    let (mut th_min, mut th_max, mut w_min, mut w_max) = (f32::INFINITY, f32::NEG_INFINITY, f32::INFINITY, f32::NEG_INFINITY);
+    for &(_,th,w) in series.iter() { th_min=th_min.min(th); th_max=th_max.max(th); w_min=w_min.min(w); w_max=w_max.max(w); }
+    if (th_max-th_min).abs()<1e-6 { th_max = th_min+1.0; }
+    if (w_max-w_min).abs()<1e-6 { w_max = w_min+1.0; }
+    let to_screen = |th: f32, w: f32| pos2(rect.left() + (th-th_min)/(th_max-th_min)*rect.width(), rect.bottom() - (w-w_min)/(w_max-w_min)*rect.height());
+    // axes through the origin when it is in view
+    let axis = egui::Stroke::new(1.0, egui::Color32::from_gray(60));
+    if th_min <= 0.0 && th_max >= 0.0 { let x = to_screen(0.0, w_min).x; painter.line_segment([pos2(x, rect.top()), pos2(x, rect.bottom())], axis); }
+    if w_min <= 0.0 && w_max >= 0.0 { let y = to_screen(th_min, 0.0).y; painter.line_segment([pos2(rect.left(), y), pos2(rect.right(), y)], axis); }
+    let count = series.len() as f32;
+    let mut prev: Option<egui::Pos2> = None;
+    for (i, &(_,th,w)) in series.iter().enumerate() {
+        let p = to_screen(th, w);
+        if let Some(p0)=prev { painter.line_segment([p0,p], (1.2, color.gamma_multiply(0.2 + 0.8 * (i as f32 / count)))); }
+        prev = Some(p);
+    }
+    if let Some(p) = prev { painter.circle_filled(p, 3.0, color); }
+}
@@ -0,0 +1,7 @@
+// GUI-free part of the n-pendulum: chain model, RK4 solver, export and config files.
+// Usable as a dependency with `default-features = false`.
+pub mod pendulum;
+pub mod solver;
+pub mod export;
+pub mod rng;
+pub mod config;
@@ -1,5 +1,3 @@
-mod pendulum;
-mod solver;
 mod gui;
 
 fn main() {
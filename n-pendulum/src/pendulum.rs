@@ -1,32 +1,179 @@
 use std::f32;
 
+use serde::{Deserialize, Serialize};
+
 pub const MAX_LINKS: usize = 7;
 pub const HISTORY_SECONDS: f32 = 60.0;
 pub const HISTORY_SAMPLES: usize = 1024;
 
-#[derive(Clone, Copy)]
-pub struct LinkParams { pub length: f32, pub mass: f32 }
+/// `coupling` is the stiffness (N·m/rad) of the torsional spring joining this
+/// link to the one above it; it is unused for the first link, which hangs from
+/// the fixed pivot. `damping` (N·m·s/rad) resists the link's own angular velocity.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LinkParams { pub length: f32, pub mass: f32, pub coupling: f32, pub damping: f32 }
+
+pub const DEFAULT_COUPLING: f32 = 5.0;
+pub const DEFAULT_GRAVITY: f32 = 9.81;
+
+/// Gravity and the active links' parameters as parallel slices, one entry per link.
+#[derive(Clone, Copy, Debug)]
+pub struct ChainParams<'a> { pub g: f32, pub lengths: &'a [f32], pub masses: &'a [f32], pub couplings: &'a [f32], pub dampings: &'a [f32] }
 
 // Non-method implementations that operate on plain slices so callers can
 // use local copies of parameters and avoid borrowing a larger struct while
 // mutably borrowing temporary buffers.
-pub fn accelerations_impl(n: usize, lengths: &[f32], masses: &[f32], thetas: &[f32], _omegas: &[f32], out: &mut [f32]) {
-    let g = 9.81f32;
+
+/// Angular accelerations of a chain of point masses on massless rigid rods,
+/// from the Lagrangian. Each evaluation builds and solves
+/// `M(theta) * alpha = b(theta, omega)` where, with `mu_ij` the mass hanging at
+/// or below both links i and j,
+///   `M_ij = mu_ij l_i l_j cos(theta_i - theta_j)`
+///   `b_i  = -sum_j mu_ij l_i l_j sin(theta_i - theta_j) omega_j^2 - g l_i mu_ii sin(theta_i) + Q_i`
+/// and `Q_i` are the torques of the torsional joint springs, `couplings[i]`
+/// being the joint between links i-1 and i, minus the viscous `dampings[i] * omega_i`.
+pub fn accelerations_impl(n: usize, p: ChainParams, thetas: &[f32], omegas: &[f32], out: &mut [f32]) {
+    let ChainParams { g, lengths, masses, couplings, dampings } = p;
+    // mass at or below each link
+    let mut tail = [0.0f32; MAX_LINKS];
+    let mut acc = 0.0f32;
+    for i in (0..n).rev() { acc += masses[i]; tail[i] = acc; }
+
+    let mut m = [[0.0f32; MAX_LINKS]; MAX_LINKS];
+    let mut b = [0.0f32; MAX_LINKS];
+    for i in 0..n {
+        for j in 0..n {
+            let mu = tail[i.max(j)];
+            let d = thetas[i] - thetas[j];
+            m[i][j] = mu * lengths[i] * lengths[j] * d.cos();
+            b[i] -= mu * lengths[i] * lengths[j] * d.sin() * omegas[j] * omegas[j];
+        }
+        b[i] -= g * lengths[i] * tail[i] * thetas[i].sin();
+        if i > 0 { b[i] -= couplings[i] * (thetas[i] - thetas[i - 1]); }
+        if i + 1 < n { b[i] -= couplings[i + 1] * (thetas[i] - thetas[i + 1]); }
+        b[i] -= dampings[i] * omegas[i];
+    }
+    solve_in_place(n, &mut m, &mut b);
+    out[..n].copy_from_slice(&b[..n]);
+}
+
+/// Gaussian elimination with partial pivoting; the solution replaces `b`.
+/// The chain's mass matrix is symmetric positive definite, so a zero pivot only
+/// happens for degenerate links, which get zero acceleration.
+fn solve_in_place(n: usize, m: &mut [[f32; MAX_LINKS]; MAX_LINKS], b: &mut [f32; MAX_LINKS]) {
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&x, &y| m[x][col].abs().total_cmp(&m[y][col].abs())).unwrap_or(col);
+        m.swap(col, pivot); b.swap(col, pivot);
+        let p = m[col][col];
+        if p.abs() < 1e-12 { continue; }
+        let pivot_row = m[col];
+        for row in col + 1..n {
+            let f = m[row][col] / p;
+            for (dst, src) in m[row][col..n].iter_mut().zip(&pivot_row[col..n]) { *dst -= f * src; }
+            b[row] -= f * b[col];
+        }
+    }
+    for row in (0..n).rev() {
+        let mut sum = b[row];
+        for c in row + 1..n { sum -= m[row][c] * b[c]; }
+        b[row] = if m[row][row].abs() < 1e-12 { 0.0 } else { sum / m[row][row] };
+    }
+}
+
+/// (kinetic, potential) energy of the chain, consistent with `accelerations_impl`.
+/// Potential is zero with every link hanging straight down.
+pub fn chain_energy(n: usize, g: f32, lengths: &[f32], masses: &[f32], couplings: &[f32], thetas: &[f32], omegas: &[f32]) -> (f32, f32) {
+    let mut kinetic = 0.0f32; let mut potential = 0.0f32;
+    let (mut vx, mut vy, mut drop) = (0.0f32, 0.0f32, 0.0f32);
+    for i in 0..n {
+        vx += lengths[i] * omegas[i] * thetas[i].cos();
+        vy += lengths[i] * omegas[i] * thetas[i].sin();
+        drop += lengths[i] * (1.0 - thetas[i].cos());
+        kinetic += 0.5 * masses[i] * (vx * vx + vy * vy);
+        potential += masses[i] * g * drop;
+        if i > 0 { potential += 0.5 * couplings[i] * (thetas[i] - thetas[i - 1]).powi(2); }
+    }
+    (kinetic, potential)
+}
+
+/// Cartesian velocity of every bob from forward kinematics. Bob `i` moves with
+/// the sum of the tangential velocities of links `0..=i` (screen y points down).
+pub fn bob_velocities(n: usize, lengths: &[f32], thetas: &[f32], omegas: &[f32], out: &mut [(f32, f32)]) {
+    let (mut vx, mut vy) = (0.0f32, 0.0f32);
     for i in 0..n {
-        let mut torque = -g / lengths[i] * thetas[i].sin();
-        if i > 0 { let k = 5.0f32; torque += -k * (thetas[i] - thetas[i - 1]); }
-        if i + 1 < n { let k = 5.0f32; torque += -k * (thetas[i] - thetas[i + 1]); }
-        let im = masses[i] * lengths[i] * lengths[i];
-        out[i] = if im.abs() < 1e-12 { 0.0 } else { torque / im };
+        vx += lengths[i] * omegas[i] * thetas[i].cos();
+        vy -= lengths[i] * omegas[i] * thetas[i].sin();
+        out[i] = (vx, vy);
     }
 }
 
-pub fn deriv_impl(n: usize, lengths: &[f32], masses: &[f32], y: &[f32], out: &mut [f32]) {
+/// Derivative of the stacked state `y = [theta_0, omega_0, ...]` into `out`.
+/// `thetas`, `omegas` and `acc` are caller-owned scratch of at least `n` values.
+pub fn deriv_impl(n: usize, p: ChainParams, y: &[f32], out: &mut [f32], thetas: &mut [f32], omegas: &mut [f32], acc: &mut [f32]) {
     for i in 0..n { out[2 * i] = y[2 * i + 1]; }
-    let mut thetas = [0.0f32; MAX_LINKS];
-    let mut omegas = [0.0f32; MAX_LINKS];
-    let mut acc = [0.0f32; MAX_LINKS];
     for i in 0..n { thetas[i] = y[2 * i]; omegas[i] = y[2 * i + 1]; }
-    accelerations_impl(n, lengths, masses, &thetas[..n], &omegas[..n], &mut acc[..n]);
+    accelerations_impl(n, p, &thetas[..n], &omegas[..n], &mut acc[..n]);
     for i in 0..n { out[2 * i + 1] = acc[i]; }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{step_rk4, Scratch};
+
+    const LENGTHS: [f32; 3] = [1.0, 0.8, 0.6];
+    const MASSES: [f32; 3] = [1.0, 0.7, 0.5];
+
+    fn chain<'a>(g: f32, couplings: &'a [f32], dampings: &'a [f32]) -> ChainParams<'a> {
+        ChainParams { g, lengths: &LENGTHS, masses: &MASSES, couplings, dampings }
+    }
+
+    #[test]
+    fn undamped_small_angles_conserve_energy() {
+        let (couplings, dampings) = ([DEFAULT_COUPLING; 3], [0.0f32; 3]);
+        let p = chain(DEFAULT_GRAVITY, &couplings, &dampings);
+        let (mut theta, mut omega) = ([0.1f32, -0.05, 0.08], [0.0f32; 3]);
+        let total = |theta: &[f32], omega: &[f32]| {
+            let (k, v) = chain_energy(3, p.g, p.lengths, p.masses, p.couplings, theta, omega);
+            k + v
+        };
+        let start = total(&theta, &omega);
+        let mut scratch = Scratch::default();
+        // 5 s at the app's 5 ms substep
+        for _ in 0..1000 {
+            step_rk4(3, p, &mut theta, &mut omega, 0.005, &mut scratch);
+            let drift = (total(&theta, &omega) - start).abs() / start;
+            assert!(drift < 1e-3, "relative energy drift {drift}");
+        }
+    }
+
+    #[test]
+    fn zero_gravity_aligned_links_do_not_accelerate() {
+        let (couplings, dampings) = ([DEFAULT_COUPLING; 3], [0.0f32; 3]);
+        let y = [0.4f32, 0.0, 0.4, 0.0, 0.4, 0.0];
+        let mut out = [1.0f32; 6];
+        let (mut thetas, mut omegas, mut acc) = ([0.0f32; 3], [0.0f32; 3], [0.0f32; 3]);
+        deriv_impl(3, chain(0.0, &couplings, &dampings), &y, &mut out, &mut thetas, &mut omegas, &mut acc);
+        for i in 0..3 {
+            assert_eq!(out[2 * i], 0.0);
+            assert!(out[2 * i + 1].abs() < 1e-6, "link {i} accelerates at {}", out[2 * i + 1]);
+        }
+    }
+
+    #[test]
+    fn damped_link_amplitude_decays_monotonically() {
+        let (couplings, dampings) = ([0.0f32], [0.3f32]);
+        let p = ChainParams { g: DEFAULT_GRAVITY, lengths: &LENGTHS[..1], masses: &MASSES[..1], couplings: &couplings, dampings: &dampings };
+        let (mut theta, mut omega) = ([0.5f32], [0.0f32]);
+        let mut scratch = Scratch::default();
+        let mut peaks = Vec::new();
+        for _ in 0..4000 {
+            let before = omega[0];
+            step_rk4(1, p, &mut theta, &mut omega, 0.005, &mut scratch);
+            // ω changes sign at each turning point of the swing
+            if before != 0.0 && (before < 0.0) != (omega[0] < 0.0) { peaks.push(theta[0].abs()); }
+        }
+        assert!(peaks.len() >= 6, "only {} turning points", peaks.len());
+        for pair in peaks.windows(2) { assert!(pair[1] < pair[0], "amplitude grew: {pair:?}"); }
+        assert!(peaks[peaks.len() - 1] < 0.5 * peaks[0]);
+    }
+}
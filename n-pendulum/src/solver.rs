@@ -1,31 +1,35 @@
-use crate::pendulum::{deriv_impl};
-use crate::pendulum::MAX_LINKS;
+use pendulum_core::{rk4_step_slice, Rk4Stages};
 
-/// Perform one RK4 step on the state arrays. `theta` and `omega` are updated in-place.
-pub fn step_rk4(n: usize, params_lengths: &[f32], params_masses: &[f32], theta: &mut [f32], omega: &mut [f32], dt: f32, k1: &mut [f32], k2: &mut [f32], k3: &mut [f32], k4: &mut [f32]) {
-    // Build a small stacked state vector y of size 2*n, using local arrays for safety.
-    let mut y_local = [0.0f32; 2 * MAX_LINKS];
-    for i in 0..n { y_local[2*i] = theta[i]; y_local[2*i+1] = omega[i]; }
+use crate::pendulum::{deriv_impl, ChainParams};
+use crate::pendulum::MAX_LINKS;
 
-    // k1
-    deriv_impl(n, params_lengths, params_masses, &y_local[..2*n], k1);
+/// Buffers reused by every `step_rk4` call, sized for `MAX_LINKS`, so a frame
+/// of substeps sets up no fresh arrays. `k1..k4` hold the stage derivatives,
+/// `y` and `tmp` the stacked state and stage state, and `thetas`, `omegas` and
+/// `acc` the unstacked copies each derivative evaluation works on.
+pub struct Scratch {
+    pub k1: Vec<f32>, pub k2: Vec<f32>, pub k3: Vec<f32>, pub k4: Vec<f32>,
+    y: Vec<f32>, tmp: Vec<f32>,
+    thetas: Vec<f32>, omegas: Vec<f32>, acc: Vec<f32>,
+}
 
-    // k2
-    let mut tmp = [0.0f32; 2 * MAX_LINKS];
-    for i in 0..2*n { tmp[i] = y_local[i] + 0.5*dt*k1[i]; }
-    deriv_impl(n, params_lengths, params_masses, &tmp[..2*n], k2);
+impl Default for Scratch {
+    fn default() -> Self {
+        let stacked = || vec![0.0f32; 2 * MAX_LINKS];
+        let links = || vec![0.0f32; MAX_LINKS];
+        Self { k1: stacked(), k2: stacked(), k3: stacked(), k4: stacked(), y: stacked(), tmp: stacked(), thetas: links(), omegas: links(), acc: links() }
+    }
+}
 
-    // k3
-    for i in 0..2*n { tmp[i] = y_local[i] + 0.5*dt*k2[i]; }
-    deriv_impl(n, params_lengths, params_masses, &tmp[..2*n], k3);
+/// Perform one RK4 step on the state arrays. `theta` and `omega` are updated in-place.
+pub fn step_rk4(n: usize, params: ChainParams, theta: &mut [f32], omega: &mut [f32], dt: f32, scratch: &mut Scratch) {
+    let Scratch { k1, k2, k3, k4, y, tmp, thetas, omegas, acc } = scratch;
+    // stack the state as y = [theta_0, omega_0, theta_1, omega_1, ...]
+    for i in 0..n { y[2*i] = theta[i]; y[2*i+1] = omega[i]; }
 
-    // k4
-    for i in 0..2*n { tmp[i] = y_local[i] + dt*k3[i]; }
-    deriv_impl(n, params_lengths, params_masses, &tmp[..2*n], k4);
+    rk4_step_slice(&mut y[..2*n], dt, Rk4Stages { k1, k2, k3, k4, tmp }, |y, out| {
+        deriv_impl(n, params, y, out, thetas, omegas, acc)
+    });
 
-    // advance
-    for i in 0..n {
-        theta[i] += (dt/6.0) * (k1[2*i] + 2.0*k2[2*i] + 2.0*k3[2*i] + k4[2*i]);
-        omega[i] += (dt/6.0) * (k1[2*i+1] + 2.0*k2[2*i+1] + 2.0*k3[2*i+1] + k4[2*i+1]);
-    }
+    for i in 0..n { theta[i] = y[2*i]; omega[i] = y[2*i+1]; }
 }
@@ -0,0 +1,66 @@
+// src/capture.rs
+//
+// Window screenshots cropped to a region and written as PNGs, singly or as
+// a numbered frame sequence. Shared by the GUI apps behind `capture`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use egui::{ColorImage, Rect, pos2};
+
+/// Crop `region` (in points) out of a window screenshot and write it as a PNG.
+pub fn save_screenshot_region(
+    shot: &ColorImage,
+    region: Rect,
+    pixels_per_point: f32,
+    path: &Path,
+) -> io::Result<()> {
+    let ppp = pixels_per_point.max(0.1);
+    let screen = Rect::from_min_max(
+        pos2(0.0, 0.0),
+        pos2(shot.width() as f32 / ppp, shot.height() as f32 / ppp),
+    );
+    let region = region.intersect(screen);
+    if !region.is_positive() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "region is off screen"));
+    }
+    let crop = shot.region(&region, Some(ppp));
+    image::save_buffer(
+        path,
+        crop.as_raw(),
+        crop.width() as u32,
+        crop.height() as u32,
+        image::ColorType::Rgba8,
+    )
+    .map_err(io::Error::other)
+}
+
+/// A PNG frame sequence being written one screenshot per frame while the
+/// sim runs.
+pub struct FrameSequence {
+    pub dir: PathBuf,
+    pub total_frames: usize,
+    pub captured: usize,
+}
+
+impl FrameSequence {
+    /// Create `dir` and prepare to write `total_frames` frames into it.
+    pub fn start(dir: &Path, total_frames: usize) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self { dir: dir.to_path_buf(), total_frames: total_frames.max(1), captured: 0 })
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.captured >= self.total_frames
+    }
+
+    /// Crop `region` out of a window screenshot and write it as the next
+    /// `frame_00000.png`, ...
+    pub fn save_frame(&mut self, shot: &ColorImage, region: Rect, pixels_per_point: f32) -> io::Result<()> {
+        let path = self.dir.join(format!("frame_{:05}.png", self.captured));
+        save_screenshot_region(shot, region, pixels_per_point, &path)?;
+        self.captured += 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+// src/json.rs
+//
+// JSON state and config files. `serde_json` writes the shortest decimal that
+// parses back to the same `f32`, so a reloaded state steps exactly like the
+// saved one. Each crate checks its own values after reading.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Write `value` to `path` as pretty-printed JSON.
+pub fn save_json<T: Serialize>(value: &T, path: &Path) -> io::Result<()> {
+    let text = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+    fs::write(path, text)
+}
+
+/// Read a JSON file, reporting malformed contents as [`invalid`].
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> io::Result<T> {
+    let text = fs::read_to_string(path)?;
+    serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))
+}
+
+/// An `InvalidData` error for a file that doesn't hold a usable value.
+pub fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn floats_round_trip_bit_for_bit() {
+        let values = vec![
+            1.0f32 / 3.0,
+            0.1,
+            std::f32::consts::PI - f32::EPSILON,
+            -0.0,
+            f32::MIN_POSITIVE,
+            1e-45,
+            f32::MAX,
+            12345.679,
+        ];
+        let path = std::env::temp_dir().join(format!("json-floats-{}.json", std::process::id()));
+        save_json(&values, &path).unwrap();
+        let back: io::Result<Vec<f32>> = load_json(&path);
+        let _ = fs::remove_file(&path);
+        let bits = |v: &[f32]| v.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+        assert_eq!(bits(&back.unwrap()), bits(&values));
+    }
+
+    #[test]
+    fn malformed_file_is_invalid_data() {
+        let path = std::env::temp_dir().join(format!("json-malformed-{}.json", std::process::id()));
+        fs::write(&path, "[1.0, ").unwrap();
+        let back: io::Result<Vec<f32>> = load_json(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(back.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,102 @@
+// src/lib.rs
+//
+// Integrator, export and state-file helpers shared by the pendulum crates.
+// Each crate supplies its own equations of motion as a derivative closure.
+
+#[cfg(feature = "capture")]
+pub mod capture;
+pub mod json;
+pub mod npy;
+
+/// One classic fourth-order Runge–Kutta step of `dt` for `dy/dt = f(y)`.
+/// Time-dependent systems carry `t` as an extra state component with
+/// derivative 1, so every stage sees the right time.
+pub fn rk4_step<const N: usize, F>(state: [f32; N], dt: f32, mut f: F) -> [f32; N]
+where
+    F: FnMut(&[f32; N]) -> [f32; N],
+{
+    let offset = |k: &[f32; N], h: f32| -> [f32; N] { std::array::from_fn(|i| state[i] + h * k[i]) };
+    let k1 = f(&state);
+    let k2 = f(&offset(&k1, 0.5 * dt));
+    let k3 = f(&offset(&k2, 0.5 * dt));
+    let k4 = f(&offset(&k3, dt));
+    std::array::from_fn(|i| state[i] + (dt / 6.0) * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]))
+}
+
+/// Caller-owned working space for [`rk4_step_slice`]: `k1..k4` receive the
+/// stage derivatives and `tmp` holds the stage states. Each must be at least
+/// as long as the state.
+pub struct Rk4Stages<'a> {
+    pub k1: &'a mut [f32],
+    pub k2: &'a mut [f32],
+    pub k3: &'a mut [f32],
+    pub k4: &'a mut [f32],
+    pub tmp: &'a mut [f32],
+}
+
+/// Slice form of [`rk4_step`] for states whose length is only known at run
+/// time. `f(y, out)` writes `dy/dt` into `out`, and `stages` is reused so
+/// repeated steps don't allocate. `state` is advanced in place.
+pub fn rk4_step_slice<F>(state: &mut [f32], dt: f32, stages: Rk4Stages, mut f: F)
+where
+    F: FnMut(&[f32], &mut [f32]),
+{
+    let n = state.len();
+    let Rk4Stages { k1, k2, k3, k4, tmp } = stages;
+    let (k1, k2, k3, k4, tmp) = (&mut k1[..n], &mut k2[..n], &mut k3[..n], &mut k4[..n], &mut tmp[..n]);
+    // tmp = state + h * k
+    let offset = |tmp: &mut [f32], state: &[f32], k: &[f32], h: f32| {
+        for ((t, y), k) in tmp.iter_mut().zip(state).zip(k) {
+            *t = y + h * k;
+        }
+    };
+
+    f(state, k1);
+    offset(tmp, state, k1, 0.5 * dt);
+    f(tmp, k2);
+    offset(tmp, state, k2, 0.5 * dt);
+    f(tmp, k3);
+    offset(tmp, state, k3, dt);
+    f(tmp, k4);
+    for (i, y) in state.iter_mut().enumerate() {
+        *y += (dt / 6.0) * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Harmonic oscillator `x'' = -x` from `x = 1, v = 0`; exact `x = cos t`.
+    fn oscillator(y: &[f32; 2]) -> [f32; 2] {
+        [y[1], -y[0]]
+    }
+
+    #[test]
+    fn matches_harmonic_oscillator() {
+        let dt = 0.01f32;
+        let mut y = [1.0f32, 0.0];
+        for i in 1..=1000 {
+            y = rk4_step(y, dt, oscillator);
+            let t = i as f32 * dt;
+            assert!((y[0] - t.cos()).abs() < 1e-4, "x({t}) = {}, want {}", y[0], t.cos());
+            assert!((y[1] + t.sin()).abs() < 1e-4, "v({t}) = {}, want {}", y[1], -t.sin());
+        }
+    }
+
+    #[test]
+    fn slice_form_agrees_with_array_form() {
+        let dt = 0.05f32;
+        let mut array = [1.0f32, 0.0];
+        let mut slice = [1.0f32, 0.0];
+        let (mut k1, mut k2, mut k3, mut k4, mut tmp) = ([0.0f32; 2], [0.0f32; 2], [0.0f32; 2], [0.0f32; 2], [0.0f32; 2]);
+        for _ in 0..200 {
+            array = rk4_step(array, dt, oscillator);
+            let stages = Rk4Stages { k1: &mut k1, k2: &mut k2, k3: &mut k3, k4: &mut k4, tmp: &mut tmp };
+            rk4_step_slice(&mut slice, dt, stages, |y, out| {
+                out.copy_from_slice(&oscillator(&[y[0], y[1]]));
+            });
+            assert_eq!(array, slice);
+        }
+    }
+}
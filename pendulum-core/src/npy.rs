@@ -0,0 +1,60 @@
+// src/npy.rs
+
+use std::io::{self, Write};
+
+/// Write a row-major 2D `float32` array in the NumPy `.npy` (v1.0) format.
+/// `values` must yield exactly `rows * cols` items; otherwise nothing is
+/// written and an `InvalidInput` error is returned.
+pub fn write_npy_f32<W, I>(mut w: W, rows: usize, cols: usize, values: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = f32>,
+{
+    let values: Vec<f32> = values.into_iter().collect();
+    if values.len() != rows * cols {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("expected {} values, got {}", rows * cols, values.len()),
+        ));
+    }
+    let mut header = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}",
+        rows, cols
+    );
+    // magic (6) + version (2) + header length (2) + header must be a multiple of 64
+    let unpadded = 10 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    w.write_all(b"\x93NUMPY")?;
+    w.write_all(&[1, 0])?;
+    w.write_all(&(header.len() as u16).to_le_bytes())?;
+    w.write_all(header.as_bytes())?;
+    for v in values {
+        w.write_all(&v.to_le_bytes())?;
+    }
+    w.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_is_padded_and_data_follows() {
+        let mut out = Vec::new();
+        write_npy_f32(&mut out, 2, 3, (0..6).map(|i| i as f32)).unwrap();
+        let header_len = u16::from_le_bytes([out[8], out[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        assert_eq!(out.len(), 10 + header_len + 6 * 4);
+        assert_eq!(&out[out.len() - 4..], &5.0f32.to_le_bytes());
+    }
+
+    #[test]
+    fn wrong_count_writes_nothing() {
+        let mut out = Vec::new();
+        let err = write_npy_f32(&mut out, 2, 3, [1.0, 2.0]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(out.is_empty());
+    }
+}
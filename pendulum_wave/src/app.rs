@@ -1,29 +1,104 @@
+use std::path::Path;
+
 use eframe::egui;
-use crate::simulation::PendulumSimulation;
+use pendulum_core::capture::FrameSequence;
+use pendulum_wave::pendulum::Pendulum;
+use pendulum_wave::state::WaveState;
+
+use crate::simulation::{ColorScheme, PendulumSimulation};
 
-#[derive(Default)]
 pub struct PendulumWaveApp {
     pub simulation: PendulumSimulation,
     pub paused: bool,
     pub last_update: Option<f64>,
+    /// Frame time not yet simulated, always less than one `FIXED_STEP`.
+    accumulator: f32,
+    // frame-sequence recording
+    pub record_dir: String,
+    pub record_seconds: f32,
+    recording: Option<FrameSequence>,
+    record_status: Option<String>,
+    pub state_path: String,
+    state_status: Option<String>,
+    /// Canvas region (points) and scale of the screenshot requested this frame.
+    screenshot_request: Option<(egui::Rect, f32)>,
+    canvas_rect: egui::Rect,
+}
+
+impl Default for PendulumWaveApp {
+    fn default() -> Self {
+        Self {
+            simulation: PendulumSimulation::default(),
+            paused: false,
+            last_update: None,
+            accumulator: 0.0,
+            record_dir: "wave_frames".into(),
+            record_seconds: 10.0,
+            recording: None,
+            record_status: None,
+            state_path: "wave_state.json".into(),
+            state_status: None,
+            screenshot_request: None,
+            canvas_rect: egui::Rect::NOTHING,
+        }
+    }
 }
 
 impl PendulumWaveApp {
+    /// Most frame time simulated at once, so a stall doesn't leave a
+    /// backlog of substeps that makes the next frame slower still.
+    const MAX_DELTA_TIME: f32 = 0.1;
+    /// Simulation step, independent of the display refresh rate.
+    const FIXED_STEP: f32 = 1.0 / 240.0;
+    /// Playback rate of recorded frame sequences.
+    const RECORD_FPS: usize = 30;
+
     pub fn update_simulation(&mut self, current_time: f64) {
+        // Keep the clock moving while paused so resuming starts with a small dt
         if self.paused {
+            self.last_update = Some(current_time);
+            return;
+        }
+
+        // each recorded frame is exactly 1 / RECORD_FPS of simulated time,
+        // however long the real frame took, so playback runs at true speed
+        if self.recording.is_some() {
+            for _ in 0..(1.0 / (Self::RECORD_FPS as f32 * Self::FIXED_STEP)).round() as usize {
+                self.simulation.update(Self::FIXED_STEP);
+            }
+            self.last_update = Some(current_time);
             return;
         }
         
         if let Some(last_time) = self.last_update {
             let delta_time = (current_time - last_time) as f32;
-            self.simulation.update(delta_time);
+            self.accumulator = (self.accumulator + delta_time.max(0.0)).min(Self::MAX_DELTA_TIME);
+            while self.accumulator >= Self::FIXED_STEP {
+                self.simulation.update(Self::FIXED_STEP);
+                self.accumulator -= Self::FIXED_STEP;
+            }
         }
         self.last_update = Some(current_time);
     }
+
+    fn toggle_recording(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            self.record_status = Some(format!("Stopped after {} frames", recording.captured));
+            return;
+        }
+        let frames = (self.record_seconds * Self::RECORD_FPS as f32).round() as usize;
+        match FrameSequence::start(Path::new(&self.record_dir), frames) {
+            Ok(recording) => {
+                self.recording = Some(recording);
+                self.record_status = None;
+            }
+            Err(e) => self.record_status = Some(format!("Recording failed: {}", e)),
+        }
+    }
 }
 
 impl eframe::App for PendulumWaveApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         let current_time = ctx.input(|i| i.time);
         self.update_simulation(current_time);
         
@@ -31,33 +106,209 @@ impl eframe::App for PendulumWaveApp {
             ui.heading("Pendulum Wave Simulation - N Pendulums (2.5D View)");
             
             // Controls
-            ui.horizontal(|ui| {
+            ui.horizontal_wrapped(|ui| {
                 if ui.button(if self.paused { "Resume" } else { "Pause" }).clicked() {
                     self.paused = !self.paused;
+                    // No frames are painted while paused, so drop the stale timestamp
+                    self.last_update = None;
                 }
                 if ui.button("Reset").clicked() {
                     self.simulation.reset();
                     self.last_update = None;
+                    self.accumulator = 0.0;
+                }
+                let mut count = self.simulation.pendulums.len();
+                if ui
+                    .add(egui::Slider::new(&mut count, 1..=PendulumSimulation::MAX_PENDULUMS).text("Pendulums"))
+                    .changed()
+                {
+                    self.simulation.set_count(count);
+                }
+                let mut scheme = self.simulation.color_scheme;
+                egui::ComboBox::from_label("Colours")
+                    .selected_text(scheme.name())
+                    .show_ui(ui, |ui| {
+                        for option in ColorScheme::ALL {
+                            ui.selectable_value(&mut scheme, option, option.name());
+                        }
+                    });
+                if scheme != self.simulation.color_scheme {
+                    self.simulation.set_color_scheme(scheme);
+                }
+                let mut amplitude = self.simulation.amplitude.to_degrees();
+                if ui
+                    .add(egui::Slider::new(&mut amplitude, 5.0..=170.0).suffix("°").text("Amplitude"))
+                    .on_hover_text("Release angle, applied on the next reset")
+                    .changed()
+                {
+                    self.simulation.amplitude = amplitude.to_radians();
+                }
+                ui.checkbox(&mut self.simulation.damping_enabled, "Damping");
+                ui.add_enabled(
+                    self.simulation.damping_enabled,
+                    egui::Slider::new(&mut self.simulation.damping, 0.0..=0.5)
+                        .logarithmic(true)
+                        .suffix(" /s"),
+                );
+                let mut view_angle = self.simulation.view_angle.to_degrees();
+                if ui
+                    .add(egui::Slider::new(&mut view_angle, -30.0..=30.0).suffix("°").text("View angle"))
+                    .changed()
+                {
+                    self.simulation.view_angle = view_angle.to_radians();
+                }
+                let mut depth = self.simulation.depth_strength;
+                if ui
+                    .add(egui::Slider::new(&mut depth, 0.0..=1.0).text("Depth"))
+                    .on_hover_text("0 draws every pendulum the same size")
+                    .changed()
+                {
+                    self.simulation.set_depth_strength(depth);
+                }
+                let mut tolerance = self.simulation.align_tolerance.to_degrees();
+                if ui
+                    .add(egui::Slider::new(&mut tolerance, 1.0..=45.0).suffix("°").text("Align tolerance"))
+                    .on_hover_text("Phase spread below which the pendulums count as lined up")
+                    .changed()
+                {
+                    self.simulation.align_tolerance = tolerance.to_radians();
                 }
                 ui.label("🎯 2.5D Perspective View with Depth & Shadows");
             });
             
             // Simulation info
-            ui.label(format!("Time: {:.2}s", self.simulation.time));
+            ui.label(format!(
+                "Time: {:.2}s   Full cycle: {:.0}s (realigns in {:.1}s)   Phase spread: {:.0}°",
+                self.simulation.time,
+                self.simulation.realign_period(),
+                self.simulation.time_to_realign(),
+                self.simulation.phase_spread().to_degrees()
+            ));
+            ui.horizontal(|ui| {
+                let recording = self.recording.is_some();
+                ui.add_enabled_ui(!recording, |ui| {
+                    ui.label("Frames to");
+                    ui.text_edit_singleline(&mut self.record_dir);
+                    ui.add(egui::Slider::new(&mut self.record_seconds, 1.0..=60.0).suffix(" s"));
+                });
+                if ui
+                    .button(if recording { "⏹ Stop" } else { "⏺ Record" })
+                    .on_hover_text(format!("Write a {} fps PNG sequence", Self::RECORD_FPS))
+                    .clicked()
+                {
+                    self.toggle_recording();
+                }
+                if let Some(rec) = &self.recording {
+                    ui.label(format!("{} / {} frames", rec.captured, rec.total_frames));
+                } else if let Some(status) = &self.record_status {
+                    ui.label(status);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("State file");
+                ui.text_edit_singleline(&mut self.state_path);
+                if ui.button("Save State").on_hover_text("Every pendulum's length, angle and velocity").clicked() {
+                    self.state_status = Some(match self.simulation.state().save(Path::new(&self.state_path)) {
+                        Ok(()) => format!("Saved {} pendulums", self.simulation.pendulums.len()),
+                        Err(e) => format!("Save failed: {}", e),
+                    });
+                }
+                if ui.button("Load State").clicked() {
+                    let loaded = WaveState::load(Path::new(&self.state_path), PendulumSimulation::MAX_PENDULUMS);
+                    self.state_status = Some(match loaded {
+                        Ok(state) => {
+                            self.simulation.apply_state(&state);
+                            self.last_update = None;
+                            self.accumulator = 0.0;
+                            format!("Loaded {} pendulums", state.pendulums.len())
+                        }
+                        Err(e) => format!("Load failed: {}", e),
+                    });
+                }
+                if let Some(status) = &self.state_status {
+                    ui.label(status);
+                }
+            });
+
             ui.label("Each pendulum has a slightly different length creating wave patterns");
+
+            egui::CollapsingHeader::new("Individual pendulums").show(ui, |ui| {
+                if ui.button("Reset to formula").clicked() {
+                    self.simulation.reset_to_formula();
+                    self.last_update = None;
+                    self.accumulator = 0.0;
+                }
+                let amplitude = self.simulation.amplitude;
+                egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                    egui::Grid::new("pendulum_edit").striped(true).show(ui, |ui| {
+                        ui.label("");
+                        ui.label("Period");
+                        ui.label("Length");
+                        ui.end_row();
+                        let sim = &mut self.simulation;
+                        for (i, pendulum) in sim.pendulums.iter_mut().enumerate() {
+                            ui.colored_label(sim.colors[i], format!("P{}", i + 1));
+                            if ui
+                                .add(egui::DragValue::new(&mut pendulum.period).speed(0.001).clamp_range(0.2..=4.0).suffix(" s"))
+                                .changed()
+                            {
+                                pendulum.length = Pendulum::length_for_period(pendulum.period, amplitude);
+                            }
+                            if ui
+                                .add(egui::DragValue::new(&mut pendulum.length).speed(0.001).clamp_range(0.01..=4.0).suffix(" m"))
+                                .changed()
+                            {
+                                pendulum.period = Pendulum::period_for_length(pendulum.length, amplitude);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
             
             // Custom painting area
             let available_size = ui.available_size();
             let (rect, _response) = ui.allocate_exact_size(available_size, egui::Sense::hover());
+            self.canvas_rect = rect;
             
             // Use the painter to draw our simulation
             let painter = ui.painter();
             self.simulation.draw(painter, rect);
         });
         
+        if self.recording.is_some() && !self.paused {
+            self.screenshot_request = Some((self.canvas_rect, ctx.pixels_per_point()));
+            frame.request_screenshot();
+        }
+
         // Only request repaint when not paused
         if !self.paused {
             ctx.request_repaint();
         }
     }
+
+    fn post_rendering(&mut self, _window_size_px: [u32; 2], frame: &eframe::Frame) {
+        let Some((region, pixels_per_point)) = self.screenshot_request.take() else {
+            return;
+        };
+        let Some(recording) = &mut self.recording else {
+            return;
+        };
+        let result = match frame.screenshot() {
+            Some(shot) => recording.save_frame(&shot, region, pixels_per_point).map_err(|e| e.to_string()),
+            None => Err("screenshots are not supported by this renderer".into()),
+        };
+        match result {
+            Err(e) => {
+                self.record_status = Some(format!("Recording failed: {}", e));
+                self.recording = None;
+            }
+            Ok(()) if recording.is_done() => {
+                self.record_status =
+                    Some(format!("Saved {} frames to {}", recording.captured, recording.dir.display()));
+                self.recording = None;
+            }
+            Ok(()) => {}
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// GUI-free part of the pendulum wave: the per-pendulum model, its integrator
+// and state files. Usable as a dependency with `default-features = false`.
+pub mod pendulum;
+pub mod state;
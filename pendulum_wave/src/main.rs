@@ -3,7 +3,6 @@ use anyhow::Result;
 
 mod app;
 mod simulation;
-mod pendulum;
 
 use app::PendulumWaveApp;
 
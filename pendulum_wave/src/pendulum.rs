@@ -1,10 +1,102 @@
-use eframe::egui;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+pub const GRAVITY: f32 = 9.81;
+
+/// Longest integration step; frames are split into substeps no larger than this.
+const MAX_SUBSTEP: f32 = 0.002;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Pendulum {
     pub length: f32,
     pub angle: f32,
     pub angular_velocity: f32,
     pub period: f32,
-    pub color: egui::Color32,
-}
\ No newline at end of file
+}
+
+/// `AGM(1, cos(θ₀/2))`, the large-amplitude correction to the period.
+fn agm_cos_half(amplitude: f32) -> f32 {
+    let (mut a, mut b) = (1.0f32, (amplitude * 0.5).cos());
+    for _ in 0..8 {
+        (a, b) = (0.5 * (a + b), (a * b).sqrt());
+    }
+    a
+}
+
+impl Pendulum {
+    /// Length whose swing from rest at `amplitude` takes `period` seconds,
+    /// using the exact nonlinear period `T = 2π√(L/g) / AGM(1, cos(θ₀/2))`.
+    pub fn length_for_period(period: f32, amplitude: f32) -> f32 {
+        GRAVITY * (period * agm_cos_half(amplitude) / std::f32::consts::TAU).powi(2)
+    }
+
+    /// Inverse of `length_for_period`.
+    pub fn period_for_length(length: f32, amplitude: f32) -> f32 {
+        std::f32::consts::TAU * (length / GRAVITY).sqrt() / agm_cos_half(amplitude)
+    }
+
+    /// Advance by `delta_time` with the full `sin θ` equation. `damping` is a
+    /// rate in 1/s: each substep scales the angular velocity by `exp(-damping * dt)`.
+    pub fn step(&mut self, delta_time: f32, damping: f32) {
+        let steps = (delta_time / MAX_SUBSTEP).ceil().max(1.0) as usize;
+        let dt = delta_time / steps as f32;
+        let decay = (-damping * dt).exp();
+        let k = GRAVITY / self.length;
+        for _ in 0..steps {
+            let [angle, angular_velocity] =
+                pendulum_core::rk4_step([self.angle, self.angular_velocity], dt, |&[th, w]| [w, -k * th.sin()]);
+            self.angle = angle;
+            self.angular_velocity = angular_velocity * decay;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn released(period: f32, amplitude: f32) -> Pendulum {
+        Pendulum { length: Pendulum::length_for_period(period, amplitude), angle: amplitude, angular_velocity: 0.0, period }
+    }
+
+    /// Time from release to the third zero crossing, one full swing later
+    /// than the first, interpolated within the frame.
+    fn measured_period(mut p: Pendulum) -> f32 {
+        let dt = 1.0 / 240.0;
+        let mut crossings = Vec::new();
+        let mut t = 0.0f32;
+        while crossings.len() < 3 {
+            let before = p.angle;
+            p.step(dt, 0.0);
+            if (before > 0.0) != (p.angle > 0.0) {
+                crossings.push(t + dt * before / (before - p.angle));
+            }
+            t += dt;
+        }
+        crossings[2] - crossings[0]
+    }
+
+    #[test]
+    fn tuned_length_swings_at_the_requested_period() {
+        for amplitude in [0.1, std::f32::consts::FRAC_PI_4, 1.5] {
+            for period in [0.8, 2.2] {
+                let measured = measured_period(released(period, amplitude));
+                assert!((measured - period).abs() < 1e-3 * period, "θ₀ {amplitude}, T {period}: measured {measured}");
+            }
+        }
+    }
+
+    #[test]
+    fn pendulums_line_up_again_after_a_cycle() {
+        // as the wave tunes fifteen of them: 20 to 34 swings in one 40 s cycle
+        let (cycle, amplitude) = (40.0, std::f32::consts::FRAC_PI_4);
+        let mut pendulums: Vec<Pendulum> = (20..=34).map(|n| released(cycle / n as f32, amplitude)).collect();
+        for _ in 0..(cycle * 60.0) as usize {
+            for p in &mut pendulums {
+                p.step(1.0 / 60.0, 0.0);
+            }
+        }
+        for p in &pendulums {
+            assert!((p.angle - amplitude).abs() < 0.02, "T {}: ended at {}", p.period, p.angle);
+        }
+    }
+}
@@ -1,9 +1,108 @@
 use eframe::egui;
-use crate::pendulum::Pendulum;
+use pendulum_wave::pendulum::Pendulum;
+use pendulum_wave::state::WaveState;
+
+/// Palette used to give each pendulum its colour.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorScheme {
+    /// Evenly spaced HSV hues with alternating saturation and value.
+    #[default]
+    Classic,
+    /// Perceptually uniform viridis ramp.
+    Viridis,
+    /// Hue sweep at constant CIE lightness and chroma.
+    Hcl,
+    /// Cividis ramp, readable with deuteranopia.
+    Deuteranopia,
+}
+
+// Nine evenly spaced stops of matplotlib's viridis.
+const VIRIDIS: [[u8; 3]; 9] = [
+    [68, 1, 84], [71, 45, 123], [59, 82, 139], [44, 114, 142], [33, 145, 140],
+    [40, 174, 128], [94, 201, 98], [173, 220, 48], [253, 231, 37],
+];
+
+// Ten evenly spaced stops of cividis.
+const CIVIDIS: [[u8; 3]; 10] = [
+    [0, 34, 78], [18, 53, 112], [59, 73, 108], [87, 93, 109], [112, 113, 115],
+    [138, 134, 120], [165, 156, 116], [195, 179, 105], [225, 204, 85], [254, 232, 56],
+];
+
+impl ColorScheme {
+    pub const ALL: [ColorScheme; 4] =
+        [ColorScheme::Classic, ColorScheme::Viridis, ColorScheme::Hcl, ColorScheme::Deuteranopia];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorScheme::Classic => "Classic",
+            ColorScheme::Viridis => "Viridis",
+            ColorScheme::Hcl => "HCL sweep",
+            ColorScheme::Deuteranopia => "Deuteranopia (cividis)",
+        }
+    }
+
+    /// Colour of pendulum `index` out of `total`.
+    pub fn color(self, index: usize, total: usize) -> egui::Color32 {
+        // fraction along the palette; 0 and 1 for the first and last pendulum
+        let t = index as f32 / (total.max(2) - 1) as f32;
+        match self {
+            ColorScheme::Classic => PendulumSimulation::generate_distinct_color(index, total),
+            // the darkest end of both ramps disappears against the background
+            ColorScheme::Viridis => sample_ramp(&VIRIDIS, 0.15 + 0.85 * t),
+            ColorScheme::Deuteranopia => sample_ramp(&CIVIDIS, 0.15 + 0.85 * t),
+            ColorScheme::Hcl => lch_to_color(70.0, 50.0, 360.0 * index as f32 / total as f32),
+        }
+    }
+}
+
+/// Linear interpolation between the stops of `ramp` at `t` in [0, 1].
+fn sample_ramp(ramp: &[[u8; 3]], t: f32) -> egui::Color32 {
+    let x = t.clamp(0.0, 1.0) * (ramp.len() - 1) as f32;
+    let i = (x.floor() as usize).min(ramp.len() - 2);
+    let f = x - i as f32;
+    let mix = |c: usize| (ramp[i][c] as f32 + (ramp[i + 1][c] as f32 - ramp[i][c] as f32) * f).round() as u8;
+    egui::Color32::from_rgb(mix(0), mix(1), mix(2))
+}
+
+/// CIE LCh(ab) with a D65 white point to sRGB, clipping out-of-gamut channels.
+fn lch_to_color(l: f32, c: f32, hue_deg: f32) -> egui::Color32 {
+    let (a, b) = (c * hue_deg.to_radians().cos(), c * hue_deg.to_radians().sin());
+    let fy = (l + 16.0) / 116.0;
+    let (fx, fz) = (fy + a / 500.0, fy - b / 200.0);
+    let finv = |f: f32| if f > 6.0 / 29.0 { f * f * f } else { 3.0 * (6.0f32 / 29.0).powi(2) * (f - 4.0 / 29.0) };
+    let (x, y, z) = (0.950_47 * finv(fx), finv(fy), 1.088_83 * finv(fz));
+    let r = 3.2406 * x - 1.5372 * y - 0.4986 * z;
+    let g = -0.9689 * x + 1.8758 * y + 0.0415 * z;
+    let bl = 0.0557 * x - 0.2040 * y + 1.0570 * z;
+    let gamma = |v: f32| {
+        let v = v.clamp(0.0, 1.0);
+        let s = if v <= 0.003_130_8 { 12.92 * v } else { 1.055 * v.powf(1.0 / 2.4) - 0.055 };
+        (s * 255.0).round() as u8
+    };
+    egui::Color32::from_rgb(gamma(r), gamma(g), gamma(bl))
+}
 
 pub struct PendulumSimulation {
     pub pendulums: Vec<Pendulum>,
+    // Display colors live beside the model so `Pendulum` stays GUI-free
+    pub colors: Vec<egui::Color32>,
+    pub color_scheme: ColorScheme,
     pub time: f32,
+    /// Release angle from rest; the lengths are tuned for it. Takes effect on reset.
+    pub amplitude: f32,
+    pub damping_enabled: bool,
+    /// Damping rate in 1/s while damping is on.
+    pub damping: f32,
+    /// Tilt of the pivot rod in radians; rotates the 2.5D scene.
+    pub view_angle: f32,
+    /// How strongly pendulums shrink with depth: 0 is flat, 1 shrinks the
+    /// back one to a fifth of the front one.
+    pub depth_strength: f32,
+    /// Largest phase spread in radians that still counts as aligned.
+    pub align_tolerance: f32,
+    /// Simulated time at which the pendulums last fell into line.
+    pub last_alignment: Option<f32>,
+    aligned: bool,
     // Precomputed values for performance
     pub total_width: f32,
     pub base_spacing: f32,
@@ -11,61 +110,141 @@ pub struct PendulumSimulation {
     pub spacing_offsets: Vec<f32>,
     pub wave_points: Vec<Vec<egui::Pos2>>,
     pub wave_rect: egui::Rect,
+    /// Screen x of each wave sample and its phase offset along the wave;
+    /// both depend only on `wave_rect`, so they're rebuilt when it changes.
+    wave_xs: Vec<f32>,
+    wave_offsets: Vec<f32>,
 }
 
 impl Default for PendulumSimulation {
     fn default() -> Self {
-        let num_pendulums = 9;
-        let mut pendulums = Vec::with_capacity(num_pendulums);
-        let base_spacing = 120.0;
-        
-        // Precompute depth factors and spacing
-        let mut depth_factors = Vec::with_capacity(num_pendulums);
-        let mut spacing_offsets = Vec::with_capacity(num_pendulums);
-        let mut total_width = 0.0;
-        
+        let mut simulation = Self {
+            pendulums: Vec::new(),
+            colors: Vec::new(),
+            color_scheme: ColorScheme::default(),
+            time: 0.0,
+            amplitude: std::f32::consts::FRAC_PI_4,
+            damping_enabled: true,
+            damping: 0.006,
+            view_angle: 0.2,
+            depth_strength: 1.0,
+            align_tolerance: 10f32.to_radians(),
+            last_alignment: None,
+            aligned: false,
+            total_width: 0.0,
+            base_spacing: 120.0,
+            depth_factors: Vec::new(),
+            spacing_offsets: Vec::new(),
+            wave_points: Vec::new(),
+            wave_rect: egui::Rect::NOTHING,
+            wave_xs: Vec::new(),
+            wave_offsets: Vec::new(),
+        };
+        simulation.set_count(9);
+        simulation
+    }
+}
+
+impl PendulumSimulation {
+    pub const MAX_PENDULUMS: usize = 30;
+    /// Seconds after which every pendulum is back in phase.
+    pub const CYCLE_TIME: f32 = 40.0;
+    /// Swings per cycle of the slowest (back) pendulum.
+    const BASE_OSCILLATIONS: usize = 20;
+
+    /// Rebuild the pendulums and every per-pendulum precomputed vector for
+    /// `num_pendulums` pendulums, restarting the wave from t = 0.
+    pub fn set_count(&mut self, num_pendulums: usize) {
+        let num_pendulums = num_pendulums.clamp(1, Self::MAX_PENDULUMS);
+        self.pendulums.clear();
+        self.colors.clear();
+        self.time = 0.0;
+
         for i in 0..num_pendulums {
-            let depth_factor = 1.0 - (i as f32 * 0.1);
-            depth_factors.push(depth_factor);
-            let spacing = base_spacing * depth_factor * depth_factor;
-            spacing_offsets.push(total_width);
-            total_width += spacing;
+            // The pendulum at the back swings BASE_OSCILLATIONS times per
+            // cycle and each one in front of it once more, so all of them
+            // line up again after CYCLE_TIME.
+            let oscillations = Self::BASE_OSCILLATIONS + (num_pendulums - 1 - i);
+            let period = Self::CYCLE_TIME / oscillations as f32;
+            let length = Pendulum::length_for_period(period, self.amplitude);
             
-            // Base length increases with each pendulum
-            let length = 1.0 + (i as f32) * 0.1;
-            // Different periods create the wave effect
-            let period = 2.0 + (i as f32) * 0.2;
+            self.colors.push(self.color_scheme.color(i, num_pendulums));
             
-            // Generate unique color for each pendulum
-            let color = Self::generate_distinct_color(i, num_pendulums);
-            
-            pendulums.push(Pendulum {
+            self.pendulums.push(Pendulum {
                 length,
-                angle: std::f32::consts::FRAC_PI_2, 
+                angle: self.amplitude,
                 angular_velocity: 0.0,
                 period,
-                color,
             });
         }
+        self.layout();
         
-        // Initialize wave points with dummy values (will be updated during first draw)
-        let wave_points = vec![Vec::new(); num_pendulums];
-        let wave_rect = egui::Rect::NOTHING;
-        
-        Self {
-            pendulums,
-            time: 0.0,
-            total_width,
-            base_spacing,
-            depth_factors,
-            spacing_offsets,
-            wave_points,
-            wave_rect,
+        // Wave points are sized lazily during the next draw
+        self.wave_points = vec![Vec::new(); num_pendulums];
+    }
+
+    /// Recompute depth factors and pivot spacing from `depth_strength`.
+    fn layout(&mut self) {
+        let num_pendulums = self.pendulums.len();
+        self.depth_factors.clear();
+        self.spacing_offsets.clear();
+
+        // Spread depth from front (1.0) back towards 0.2 and shrink the
+        // spacing past 9 pendulums so the row still fits the window.
+        let depth_step = 0.8 * self.depth_strength / (num_pendulums.max(2) - 1) as f32;
+        let spacing_scale = (9.0 / num_pendulums as f32).min(1.0);
+        let mut total_width = 0.0;
+        for i in 0..num_pendulums {
+            let depth_factor = 1.0 - (i as f32 * depth_step);
+            self.depth_factors.push(depth_factor);
+            self.spacing_offsets.push(total_width);
+            total_width += self.base_spacing * spacing_scale * depth_factor * depth_factor;
         }
+        self.total_width = total_width;
+    }
+
+    /// Change the perspective strength without disturbing the motion.
+    pub fn set_depth_strength(&mut self, strength: f32) {
+        self.depth_strength = strength.clamp(0.0, 1.0);
+        self.layout();
+    }
+
+    /// Return every pendulum to the evenly spaced periods that realign after
+    /// `CYCLE_TIME`, discarding individual edits.
+    pub fn reset_to_formula(&mut self) {
+        self.set_count(self.pendulums.len());
+    }
+
+    pub fn state(&self) -> WaveState {
+        WaveState {
+            time: self.time,
+            amplitude: self.amplitude,
+            damping_enabled: self.damping_enabled,
+            damping: self.damping,
+            pendulums: self.pendulums.clone(),
+        }
+    }
+
+    /// Continue from a validated state (see `WaveState::load`), rebuilding the
+    /// colours and layout for its pendulum count.
+    pub fn apply_state(&mut self, state: &WaveState) {
+        self.amplitude = state.amplitude;
+        self.set_count(state.pendulums.len());
+        self.pendulums = state.pendulums.clone();
+        self.time = state.time;
+        self.damping_enabled = state.damping_enabled;
+        self.damping = state.damping;
+        self.last_alignment = None;
+        self.aligned = false;
+    }
+
+    /// Switch palettes, recolouring the pendulums without touching their motion.
+    pub fn set_color_scheme(&mut self, scheme: ColorScheme) {
+        self.color_scheme = scheme;
+        let total = self.pendulums.len();
+        self.colors = (0..total).map(|i| scheme.color(i, total)).collect();
     }
-}
 
-impl PendulumSimulation {
     pub fn generate_distinct_color(index: usize, total: usize) -> egui::Color32 {
         // Distribute hues evenly
         let hue = index as f32 / total as f32;
@@ -100,55 +279,86 @@ impl PendulumSimulation {
 
     pub fn update(&mut self, delta_time: f32) {
         self.time += delta_time;
-        
-        // Use iterator for better performance
+        let damping = if self.damping_enabled { self.damping } else { 0.0 };
         for pendulum in &mut self.pendulums {
-            // Simple harmonic motion with damping
-            let gravity = 9.8f32;
-            // Use fast approximation for sin (small angles)
-            let angle = pendulum.angle;
-            let sin_angle = if angle.abs() < 0.5 {
-                // Taylor series approximation for small angles: sin(x) ≈ x - x^3/6
-                angle - angle * angle * angle / 6.0
-            } else {
-                angle.sin()
-            };
-            
-            let acceleration = -gravity / pendulum.length * sin_angle;
-            
-            pendulum.angular_velocity += acceleration * delta_time;
-            pendulum.angle += pendulum.angular_velocity * delta_time;
-            
-            // Add some damping (use multiplication instead of pow for performance)
-            pendulum.angular_velocity *= 0.9999;
+            pendulum.step(delta_time, damping);
+        }
+        let aligned = self.phase_spread() <= self.align_tolerance;
+        if aligned && !self.aligned {
+            self.last_alignment = Some(self.time);
         }
+        self.aligned = aligned;
+    }
+
+    /// Largest angular distance of any pendulum's phase from the mean phase.
+    /// Phases come from the current angle and velocity, so damping and
+    /// large amplitudes are accounted for.
+    pub fn phase_spread(&self) -> f32 {
+        let phases: Vec<f32> = self
+            .pendulums
+            .iter()
+            .map(|p| (-p.angular_velocity * p.period / std::f32::consts::TAU).atan2(p.angle))
+            .collect();
+        let (sin, cos) = phases.iter().fold((0.0f32, 0.0f32), |(s, c), ph| (s + ph.sin(), c + ph.cos()));
+        let mean = sin.atan2(cos);
+        phases
+            .iter()
+            .map(|ph| (ph - mean + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI)
+            .fold(0.0f32, |max, d| max.max(d.abs()))
+    }
+
+    /// Shortest time after which every pendulum has completed a whole number
+    /// of swings, to within `align_tolerance`. Searched over multiples of
+    /// the slowest period; falls back to `CYCLE_TIME` if none is found.
+    pub fn realign_period(&self) -> f32 {
+        let slowest = self.pendulums.iter().map(|p| p.period).fold(0.0, f32::max);
+        let slack = self.align_tolerance / std::f32::consts::TAU;
+        (1..=1000)
+            .map(|k| k as f32 * slowest)
+            .find(|&t| {
+                self.pendulums.iter().all(|p| {
+                    let swings = t / p.period;
+                    (swings - swings.round()).abs() <= slack
+                })
+            })
+            .unwrap_or(Self::CYCLE_TIME)
+    }
+
+    /// Seconds until the pendulums next line up.
+    pub fn time_to_realign(&self) -> f32 {
+        let period = self.realign_period();
+        period - self.time.rem_euclid(period)
     }
-    
+
     pub fn reset(&mut self) {
         self.time = 0.0;
+        self.last_alignment = None;
+        self.aligned = false;
         for pendulum in &mut self.pendulums {
-            pendulum.angle = std::f32::consts::FRAC_PI_4;
+            pendulum.length = Pendulum::length_for_period(pendulum.period, self.amplitude);
+            pendulum.angle = self.amplitude;
             pendulum.angular_velocity = 0.0;
         }
     }
 
     pub fn draw(&mut self, painter: &egui::Painter, rect: egui::Rect) {
         let pivot_y = rect.center().y - 200.0;
-        let scale = rect.height() * 0.25;
-        let rod_angle = 0.1f32;
-        let rod_tan = rod_angle.tan();
+        let scale = rect.height() * 0.45;
+        // pivots follow half the rod tilt; the per-row depth offset makes up the rest
+        let rod_tan = (self.view_angle * 0.5).tan();
         
         // Draw perspective rod at an angle for better 3D effect
         self.draw_angled_rod(painter, rect, pivot_y);
         
         // Draw each pendulum with proper perspective spacing
         for (i, pendulum) in self.pendulums.iter().enumerate().rev() {
+            let color = self.colors[i];
             let depth_factor = self.depth_factors[i];
             let pivot_x = rect.center().x - self.total_width * 0.5 + self.spacing_offsets[i];
             
             // Apply angled rod perspective - pivot points follow the rod angle
             let rod_y_offset = (pivot_x - rect.center().x) * rod_tan;
-            let depth_y_offset = i as f32 * 8.0;
+            let depth_y_offset = i as f32 * 8.0 * self.depth_strength;
             let adjusted_pivot_y = pivot_y + depth_y_offset + rod_y_offset;
             
             let bob_x = pivot_x + pendulum.angle.sin() * pendulum.length * scale * depth_factor;
@@ -157,11 +367,11 @@ impl PendulumSimulation {
             // Draw string
             painter.line_segment(
                 [egui::pos2(pivot_x, adjusted_pivot_y), egui::pos2(bob_x, bob_y)],
-                egui::Stroke::new(2.5 * depth_factor, pendulum.color),
+                egui::Stroke::new(2.5 * depth_factor, color),
             );
             
             // Draw bob with perspective
-            self.draw_bob_perspective(painter, bob_x, bob_y, pendulum.color, depth_factor);
+            self.draw_bob_perspective(painter, bob_x, bob_y, color, depth_factor);
             
             // Draw pivot point
             painter.circle_filled(
@@ -176,17 +386,37 @@ impl PendulumSimulation {
                 egui::Align2::LEFT_CENTER,
                 format!("P{}", i + 1),
                 egui::FontId::proportional(12.0 * depth_factor),
-                pendulum.color,
+                color,
             );
         }
         
         // Draw wave pattern visualization
         self.draw_wave_pattern(painter, rect);
+        self.draw_alignment_flash(painter, rect);
+    }
+
+    /// Fading frame and label for a second after the pendulums line up.
+    fn draw_alignment_flash(&self, painter: &egui::Painter, rect: egui::Rect) {
+        const FLASH_SECONDS: f32 = 1.0;
+        let Some(at) = self.last_alignment else { return };
+        let age = self.time - at;
+        if !(0.0..FLASH_SECONDS).contains(&age) {
+            return;
+        }
+        let alpha = (255.0 * (1.0 - age / FLASH_SECONDS)) as u8;
+        let color = egui::Color32::from_rgba_unmultiplied(255, 230, 120, alpha);
+        painter.rect_stroke(rect.shrink(4.0), 8.0, egui::Stroke::new(6.0, color));
+        painter.text(
+            egui::pos2(rect.center().x, rect.top() + 24.0),
+            egui::Align2::CENTER_CENTER,
+            "Aligned",
+            egui::FontId::proportional(22.0),
+            color,
+        );
     }
 
     fn draw_angled_rod(&self, painter: &egui::Painter, rect: egui::Rect, pivot_y: f32) {
-        let rod_angle = 0.20f32;
-        let rod_tan = -rod_angle.tan();
+        let rod_tan = -self.view_angle.tan();
         
         let left_x = rect.center().x - self.total_width * 0.5 - 20.0;
         let right_x = rect.center().x + self.total_width * 0.5 + 20.0;
@@ -238,7 +468,6 @@ impl PendulumSimulation {
             egui::pos2(rect.left() + 20.0, rect.bottom() - 180.0),
             egui::vec2(rect.width() - 40.0, 150.0),
         );
-        self.wave_rect = wave_rect;
         
         // Draw wave container
         painter.rect_filled(
@@ -255,40 +484,39 @@ impl PendulumSimulation {
         
         let time_scale = 0.5;
         let amplitude = 60.0;
-        let width = wave_rect.width() as usize;
-        let step = 2;
-        let num_points = (width + step - 1) / step;
         
-        // Reuse wave points vector to avoid reallocations
-        if self.wave_points[0].len() != num_points {
-            for points in &mut self.wave_points {
-                points.clear();
-                points.reserve(num_points);
+        // Sample positions only move when the window is resized
+        if wave_rect != self.wave_rect || self.wave_xs.is_empty() {
+            self.wave_rect = wave_rect;
+            let width = wave_rect.width() as usize;
+            let step = 2;
+            let num_points = width.div_ceil(step);
+            let inv_width = 1.0 / wave_rect.width();
+            let time_scale_tau = time_scale * std::f32::consts::TAU;
+            self.wave_xs.clear();
+            self.wave_offsets.clear();
+            for x_step in 0..num_points {
+                let x = (x_step * step) as f32;
+                self.wave_xs.push(wave_rect.left() + x);
+                self.wave_offsets.push((x * inv_width) * time_scale_tau);
             }
         }
+        let center_y = wave_rect.center().y;
         
         // Draw wave for each pendulum
         for (i, pendulum) in self.pendulums.iter().enumerate() {
-            let color = pendulum.color;
+            let color = self.colors[i];
             let phase = (self.time * std::f32::consts::TAU / pendulum.period) % std::f32::consts::TAU;
             
-            // Reuse the vector for this pendulum
+            // Reuse the vector for this pendulum; after a resize or a count
+            // change it's rebuilt at the new x positions, otherwise only y moves
             let points = &mut self.wave_points[i];
-            points.clear();
-            
-            // Precompute constants
-            let inv_width = 1.0 / wave_rect.width();
-            let time_scale_tau = time_scale * std::f32::consts::TAU;
-            let center_y = wave_rect.center().y;
-            
-            // Generate points with optimized loop
-            for x_step in 0..num_points {
-                let x = (x_step * step) as f32;
-                let x_pos = wave_rect.left() + x;
-                let time_offset = (x * inv_width) * time_scale_tau;
-                let y_offset = (phase + time_offset).sin() * amplitude;
-                let y_pos = center_y + y_offset;
-                points.push(egui::pos2(x_pos, y_pos));
+            if points.len() != self.wave_xs.len() || points.first().map(|p| p.x) != self.wave_xs.first().copied() {
+                points.clear();
+                points.extend(self.wave_xs.iter().map(|&x| egui::pos2(x, center_y)));
+            }
+            for (point, offset) in points.iter_mut().zip(&self.wave_offsets) {
+                point.y = center_y + (phase + offset).sin() * amplitude;
             }
             
             if points.len() > 1 {
@@ -0,0 +1,46 @@
+use std::io;
+use std::path::Path;
+
+use pendulum_core::json::{invalid, load_json, save_json};
+use serde::{Deserialize, Serialize};
+
+use crate::pendulum::Pendulum;
+
+/// A running wave as stored in a JSON state file: every pendulum's length,
+/// angle and velocity, the clock and the damping.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WaveState {
+    pub time: f32,
+    pub amplitude: f32,
+    pub damping_enabled: bool,
+    pub damping: f32,
+    pub pendulums: Vec<Pendulum>,
+}
+
+impl WaveState {
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        save_json(self, path)
+    }
+
+    /// Read a state file. Fails if it has no pendulums or more than
+    /// `max_pendulums`, a length or period isn't positive, or any value
+    /// isn't finite.
+    pub fn load(path: &Path, max_pendulums: usize) -> io::Result<Self> {
+        let s: WaveState = load_json(path)?;
+        if s.pendulums.is_empty() || s.pendulums.len() > max_pendulums {
+            return Err(invalid(format!("state has {} pendulums, expected 1 to {}", s.pendulums.len(), max_pendulums)));
+        }
+        for (i, p) in s.pendulums.iter().enumerate() {
+            if !(p.angle.is_finite() && p.angular_velocity.is_finite()) {
+                return Err(invalid(format!("pendulum {} has a non-finite state", i + 1)));
+            }
+            if !(p.length > 0.0 && p.period > 0.0 && p.length.is_finite() && p.period.is_finite()) {
+                return Err(invalid(format!("pendulum {} needs a positive length and period", i + 1)));
+            }
+        }
+        if !(s.time.is_finite() && s.amplitude.is_finite() && s.damping.is_finite() && s.time >= 0.0 && s.damping >= 0.0) {
+            return Err(invalid("time, amplitude and damping must be finite and non-negative"));
+        }
+        Ok(s)
+    }
+}
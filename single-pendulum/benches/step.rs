@@ -0,0 +1,48 @@
+// Steps per second of each single-pendulum integrator, as a baseline against
+// regressions: `cargo bench -p single-pendulum --no-default-features`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use single_pendulum::physics::{Drag, Drive, Integrator, Params};
+use single_pendulum::simulation::Simulation;
+
+/// Substeps per iteration, about one frame's worth at the app's step size.
+const STEPS: u64 = 100;
+
+fn step_integrators(c: &mut Criterion) {
+    let mut group = c.benchmark_group("integrator");
+    group.throughput(Throughput::Elements(STEPS));
+    let p = Params { l: 1.0, m: 1.0, drag: Drag::linear(0.1), g: 9.81, drive: Drive { amplitude: 0.5, frequency: 3.0 } };
+    for integrator in Integrator::ALL {
+        group.bench_with_input(BenchmarkId::from_parameter(integrator.name()), &integrator, |b, &integrator| {
+            let (mut theta, mut omega, mut t) = (1.0f32, 0.0f32, 0.0f32);
+            b.iter(|| {
+                for _ in 0..STEPS {
+                    (theta, omega) = integrator.step(theta, omega, black_box(0.001), p, t);
+                    t += 0.001;
+                }
+                black_box(theta)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn step_simulation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simulation");
+    group.throughput(Throughput::Elements(STEPS));
+    group.bench_function("step", |b| {
+        let mut sim = Simulation { drag: 0.1, ..Simulation::default() };
+        b.iter(|| {
+            for _ in 0..STEPS {
+                sim.step(black_box(0.001));
+            }
+            black_box(sim.theta)
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, step_integrators, step_simulation);
+criterion_main!(benches);
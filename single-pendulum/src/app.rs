@@ -1,46 +1,192 @@
 // src/app.rs
 
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::io;
+use std::path::Path;
 use std::time::{Duration, Instant};
 
 use eframe::{egui, Frame};
-use egui::{Align, Context, Layout, Response, Sense};
+use egui::{Align, Context, Layout, Painter, Rect, Response, Sense};
+use serde::{Deserialize, Serialize};
 
-use crate::physics::rk4_step;
-use crate::plots::{draw_phase_plot, draw_time_series, PlotKind};
-use crate::ui::draw_pendulum;
+use single_pendulum::export::{load_history_csv, write_history_csv, write_history_npy};
+use single_pendulum::physics::{
+    elastic_energy, energy, euler_step, match_period_length, measure_period, rk4_step, rk4_step_elastic, rk45_adaptive_step,
+    small_angle_theta, symplectic_step, wrap_angle, Drag, DragModel, Drive, Integrator, Params, Spring,
+};
+use single_pendulum::replay::Replay;
+use single_pendulum::share::{SHARE_VERSION, ShareConfig};
+use single_pendulum::simulation::{RunState, Simulation};
+use single_pendulum::spectrum::{magnitude_spectrum, resample_uniform};
+use single_pendulum::sweep::{bifurcation, period_sweep, BifurcationSettings};
+use crate::plots::{
+    draw_bifurcation, draw_function_overlay, draw_lissajous_plot, draw_period_sweep, draw_phase_plot, draw_poincare_plot,
+    draw_series_overlay, draw_spectrum, draw_time_series, PlotKind, SeriesAxes, SeriesOptions, TimeAxis, TimeView,
+};
+use crate::capture::{save_screenshot_region, FrameSequence, PngTarget};
+use crate::palette::Palette;
+use crate::ui::{dragged_angle, draw_pendulum, BobScale};
 
 pub struct PendulumApp {
-    // physics
-    pub length: f32,
-    pub mass: f32,
-    pub drag: f32,
-    pub gravity: f32,
+    // physics parameters and pendulum state
+    pub sim: Simulation,
 
     // simulation
     pub running: bool,
-    start_instant: Instant,
+    // simulated seconds since reset; only advances while integrating, so
+    // history timestamps stay continuous across pauses
+    sim_time: f32,
     last_update: Instant,
     pub simulation_speed: f32,
+    /// Advance by `fixed_dt` every frame instead of the measured frame time.
+    /// Runs then repeat bit for bit, at the cost of no longer tracking real
+    /// time: a slow frame slows the motion down rather than taking a bigger step.
+    pub fixed_dt_enabled: bool,
+    pub fixed_dt: f32,
+    // temporary damping assist: (elapsed, duration) while settling
+    settle: Option<(f32, f32)>,
+    pub settle_seconds: f32,
+    // when the speed was last changed by Ctrl+scroll, for the transient readout
+    speed_changed_at: Option<Instant>,
+    // seconds of reversed motion left before the round trip is complete
+    reversal: Option<f32>,
+    /// (Δθ, Δω) in radians between the end of the last round trip and the
+    /// initial condition, with ω compared after undoing the reversal.
+    pub reversal_error: Option<(f32, f32)>,
 
     // state
-    pub theta: f32,
-    pub omega: f32,
     pub initial_theta: f32,
+    /// Angular velocity applied on reset (rad/s).
+    pub initial_omega: f32,
+    /// Angular velocity added by a kick (rad/s); negative pushes the other way.
+    pub kick_impulse: f32,
+    /// Clock escapement: each pass through the bottom adds `escapement` rad/s
+    /// in the direction of motion, so drag settles into a steady amplitude.
+    pub escapement_enabled: bool,
+    pub escapement: f32,
+    // total swept angle, never wrapped; counts full rotations over the top
+    pub theta_unwrapped: f32,
 
     // history: (time, theta_deg, omega_deg)
     history: VecDeque<(f32, f32, f32)>,
     history_capacity: usize,
     sample_accum: f32,
     sample_dt: f32,
+    // integrator step sizes: (time, last substep in ms, substeps since last sample)
+    step_history: VecDeque<(f32, f32, f32)>,
+    substeps_since_sample: u32,
+    /// (time, potential J, kinetic J) at each `history` sample, taken with
+    /// the parameters and spring stretch of that moment.
+    energy_history: VecDeque<(f32, f32, f32)>,
+    /// Optional pendulum B and its history, sampled alongside `history`.
+    pub(crate) compare: Option<ComparePendulum>,
+    compare_history: VecDeque<(f32, f32, f32)>,
+    compare_energy_history: VecDeque<(f32, f32, f32)>,
+    /// Total energy at the last reset and the (L, m, g) it was taken with;
+    /// `None` while energy isn't expected to be conserved.
+    energy_reference: Option<(f32, (f32, f32, f32, f32))>,
 
     // UI & visualization
     pub plot_seconds: f32,
+    /// Panned/zoomed view of the time series; `None` follows live data over
+    /// `plot_seconds`.
+    plot_view: Option<TimeView>,
     pub show_timeline: bool,
     pub selected_plot: PlotKind,
+    /// Plots whose y-axis frames the visible data instead of a fixed range.
+    pub auto_fit_y: HashSet<PlotKind>,
+    /// Overlay the linearised solution on the angle plot.
+    pub show_small_angle: bool,
+    pub time_axis: TimeAxis,
+    /// Draw dense time series as a min/max envelope, one column per pixel.
+    pub decimate_plots: bool,
+    pub layout: LayoutPrefs,
     pub dark_mode: bool,
+    /// Bold, distinct colours and thicker strokes in every drawing.
+    pub high_contrast: bool,
+    /// Repaint less often while animating.
+    pub reduce_motion: bool,
     pub auto_reset_history: bool,
     pub show_help: bool,
+    pub show_debug: bool,
+    // last frame's timestep (simulated seconds) and integrator substeps, for the debug panel
+    last_dt: f32,
+    last_substeps: u32,
+    pub show_revolutions: bool,
+    pub bob_scale: BobScale,
+    /// Draw the free-body diagram at the bob.
+    pub show_forces: bool,
+    pub integrator: Integrator,
+    /// Replace the rigid rod with a spring whose rest length is `sim.length`.
+    pub elastic: bool,
+    pub spring_stiffness: f32,
+    // elastic mode: rod length and its rate of change
+    pub radius: f32,
+    pub radius_rate: f32,
+    pub rk45_tolerance: f32,
+    /// Last accepted substep, carried over as the next RK45 trial step.
+    adaptive_dt: f32,
+    pub drive_enabled: bool,
+    /// Drive torque amplitude (N·m).
+    pub drive_amplitude: f32,
+    /// Drive angular frequency (rad/s).
+    pub drive_frequency: f32,
+    /// Integrated seconds since the last reset; the drive phase is
+    /// `drive_frequency * drive_time`, and a Poincaré sample is taken each
+    /// time it passes a whole cycle.
+    drive_time: f32,
+    /// (θ wrapped to [-π, π], ω) in radians, once per drive period.
+    poincare: VecDeque<(f32, f32)>,
+    /// Resolution and transient length for the bifurcation diagram.
+    pub bifurcation: BifurcationSettings,
+    /// (drive amplitude N·m, θ rad) from the last bifurcation run.
+    bifurcation_points: Vec<(f32, f32)>,
+    pub preview_params: bool,
+    pub(crate) preview: Option<ParamPreview>,
+
+    // period matching
+    pub period_target: f32,
+    period_match: Option<Option<(f32, f32)>>,
+    /// (release angle rad, period s) from the last amplitude sweep.
+    period_sweep: Vec<(f32, f32)>,
+    /// Running comparison of the three integrators, while that mode is on.
+    race: Option<IntegratorRace>,
+
+    // export
+    pub export_path: String,
+    pub csv_path: String,
+    pub state_path: String,
+    export_status: Option<String>,
+    pub presets_path: String,
+    pub new_preset_name: String,
+    preset_name_error: Option<String>,
+    pub capture_dir: String,
+    pub capture_frames: usize,
+    pub capture_fps: f32,
+    /// PNG sequence being captured, one screenshot of the canvas per frame.
+    sequence: Option<FrameSequence>,
+    /// Canvas region (points) and scale of this frame's sequence screenshot.
+    sequence_request: Option<(Rect, f32)>,
+    pub png_path: String,
+    pub png_target: PngTarget,
+    /// Set by "Save PNG"; turned into a screenshot request at the end of `update`.
+    png_pending: bool,
+    /// Region (points) and scale of a requested screenshot, saved in `post_rendering`.
+    png_request: Option<(Rect, f32)>,
+    /// Where the pendulum canvas and main plot were laid out this frame.
+    canvas_rect: Rect,
+    plot_rect: Rect,
+
+    // replay: drives the bob from a loaded CSV instead of the integrator
+    pub replay_path: String,
+    replay: Option<Replay>,
+    // record: (time, theta_deg, omega_deg) at every history sample while on
+    recording: bool,
+    recorded: VecDeque<(f32, f32, f32)>,
+
+    // share strings
+    pub share_text: String,
 
     // perf
     frame_count: u32,
@@ -53,67 +199,330 @@ pub struct PendulumApp {
     preset_to_apply: Option<usize>,
 }
 
-#[derive(Clone)]
-pub struct Preset {
-    pub name: String,
+/// Second pendulum integrated alongside the main one for comparison. It
+/// shares gravity, the integrator and the drive but has its own rod and bob.
+#[derive(Clone, Copy)]
+pub struct ComparePendulum {
+    pub length: f32,
+    pub mass: f32,
+    pub drag: f32,
+    pub initial_theta: f32,
+    pub theta: f32,
+    pub omega: f32,
+}
+
+/// The same initial condition integrated with RK4, symplectic Euler and
+/// explicit Euler in fixed 5 ms steps, for comparing how each one drifts.
+pub struct IntegratorRace {
+    /// (θ, ω) per method, in `IntegratorRace::METHODS` order.
+    states: [(f32, f32); 3],
+    time: f32,
+    /// (time, θ in degrees, ω in degrees/s) per method.
+    histories: [VecDeque<(f32, f32, f32)>; 3],
+}
+
+type StepFn = fn(f32, f32, f32, Params, f32) -> (f32, f32);
+
+impl IntegratorRace {
+    const METHODS: [(&'static str, StepFn); 3] =
+        [("RK4", rk4_step), ("Symplectic", symplectic_step), ("Euler", euler_step)];
+    const STEP: f32 = 0.005;
+
+    fn start(theta: f32, omega: f32) -> Self {
+        Self { states: [(theta, omega); 3], time: 0.0, histories: Default::default() }
+    }
+
+    /// Advance every method by `dt`, then record one sample each.
+    fn advance(&mut self, dt: f32, p: Params, capacity: usize) {
+        let mut remaining = dt;
+        while remaining > 0.0 {
+            let step = remaining.min(Self::STEP);
+            for ((_, method), state) in Self::METHODS.iter().zip(&mut self.states) {
+                let (th, w) = method(state.0, state.1, step, p, self.time);
+                // wrap like the main sim; explicit Euler may still spin up without bound
+                *state = (wrap_angle(th), w);
+            }
+            self.time += step;
+            remaining -= step;
+        }
+        for (state, history) in self.states.iter().zip(&mut self.histories) {
+            if history.len() >= capacity {
+                history.pop_front();
+            }
+            history.push_back((self.time, state.0.to_degrees(), state.1.to_degrees()));
+        }
+    }
+}
+
+/// Parameter values staged while a parameter control is hovered, plus the
+/// ghost state integrated with them. The running sim keeps its committed
+/// values until the pointer leaves the control.
+#[derive(Clone, Copy)]
+pub struct ParamPreview {
     pub length: f32,
     pub mass: f32,
     pub drag: f32,
     pub gravity: f32,
-    pub initial_angle: f32,
+    pub theta: f32,
+    pub omega: f32,
 }
 
-impl Default for PendulumApp {
+impl ParamPreview {
+    fn capture(app: &PendulumApp) -> Self {
+        Self {
+            length: app.sim.length,
+            mass: app.sim.mass,
+            drag: app.sim.drag,
+            gravity: app.sim.gravity,
+            theta: app.sim.theta,
+            omega: app.sim.omega,
+        }
+    }
+}
+
+/// Named surface gravities (m/s²) offered next to the gravity control.
+const PLANET_GRAVITY: [(&str, f32); 5] = [
+    ("Earth", 9.81),
+    ("Moon", 1.62),
+    ("Mars", 3.71),
+    ("Jupiter", 24.79),
+    ("Zero-ish", 0.1),
+];
+
+/// Energy drift (as a fraction) above which the header indicator turns red.
+const ENERGY_DRIFT_WARN: f32 = 0.05;
+
+/// Most samples a recording keeps (about 55 minutes at 60 Hz); the oldest are dropped first.
+const RECORD_CAPACITY: usize = 200_000;
+
+/// Most Poincaré points kept; the oldest are dropped first.
+const POINCARE_CAPACITY: usize = 20_000;
+
+/// Rod lengths (m) the sliders and parameter clamping allow.
+const LENGTH_RANGE: (f32, f32) = (0.1, 10.0);
+
+/// Largest drive torque (N·m) and frequency (rad/s) the controls allow.
+const DRIVE_AMPLITUDE_MAX: f32 = 20.0;
+const DRIVE_FREQUENCY_MAX: f32 = 50.0;
+
+/// Presets are read from here at startup when the file exists and parses.
+const PRESETS_FILE: &str = "presets.json";
+
+/// Storage key for [`LayoutPrefs`] in eframe's persisted app state.
+pub const LAYOUT_KEY: &str = "layout";
+
+/// How the window is split between controls, pendulum and plot; kept
+/// between runs through eframe persistence.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutPrefs {
+    /// Share of the central width given to the pendulum in the wide layout.
+    pub split_fraction: f32,
+    /// Below this central width (points) the pendulum and plot are stacked.
+    pub stack_threshold: f32,
+    /// Width of the control panel in points.
+    pub side_width: f32,
+}
+
+impl Default for LayoutPrefs {
     fn default() -> Self {
-        let presets = vec![
-            Preset {
-                name: "Simple".into(),
-                length: 1.0,
-                mass: 1.0,
-                drag: 0.0,
-                gravity: 9.81,
-                initial_angle: 45.0,
-            },
-            Preset {
-                name: "Damped".into(),
-                length: 1.0,
-                mass: 1.0,
-                drag: 0.45,
-                gravity: 9.81,
-                initial_angle: 30.0,
-            },
-            Preset {
-                name: "Long".into(),
-                length: 2.0,
-                mass: 0.6,
-                drag: 0.08,
-                gravity: 9.81,
-                initial_angle: 60.0,
-            },
-        ];
+        Self { split_fraction: 0.52, stack_threshold: 980.0, side_width: 320.0 }
+    }
+}
 
+impl LayoutPrefs {
+    /// Clamp values from an older or hand-edited store into the slider ranges.
+    fn clamped(self) -> Self {
         Self {
+            split_fraction: self.split_fraction.clamp(0.3, 0.75),
+            stack_threshold: self.stack_threshold.clamp(600.0, 1800.0),
+            side_width: self.side_width.clamp(260.0, 480.0),
+        }
+    }
+}
+
+fn preview_hot(r: &Response) -> bool {
+    r.hovered() || r.has_focus() || r.dragged()
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub length: f32,
+    pub mass: f32,
+    pub drag: f32,
+    pub gravity: f32,
+    pub initial_angle: f32,
+    /// Launch speed in °/s; older preset files without it start at rest.
+    #[serde(default)]
+    pub initial_omega: f32,
+}
+
+fn builtin_presets() -> Vec<Preset> {
+    vec![
+        Preset {
+            name: "Simple".into(),
             length: 1.0,
             mass: 1.0,
             drag: 0.0,
             gravity: 9.81,
+            initial_angle: 45.0,
+            initial_omega: 0.0,
+        },
+        Preset {
+            name: "Damped".into(),
+            length: 1.0,
+            mass: 1.0,
+            drag: 0.45,
+            gravity: 9.81,
+            initial_angle: 30.0,
+            initial_omega: 0.0,
+        },
+        Preset {
+            name: "Long".into(),
+            length: 2.0,
+            mass: 0.6,
+            drag: 0.08,
+            gravity: 9.81,
+            initial_angle: 60.0,
+            initial_omega: 0.0,
+        },
+        Preset {
+            name: "Over the top".into(),
+            length: 1.0,
+            mass: 1.0,
+            drag: 0.0,
+            gravity: 9.81,
+            initial_angle: 0.0,
+            initial_omega: 420.0,
+        },
+    ]
+}
+
+/// Read a preset list written by `save_presets`. An empty list counts as invalid.
+fn load_presets(path: &Path) -> io::Result<Vec<Preset>> {
+    let text = fs::read_to_string(path)?;
+    let presets: Vec<Preset> = serde_json::from_str(&text)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    if presets.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "no presets in file"));
+    }
+    Ok(presets)
+}
+
+fn save_presets(presets: &[Preset], path: &Path) -> io::Result<()> {
+    let text = serde_json::to_string_pretty(presets).map_err(io::Error::other)?;
+    fs::write(path, text)
+}
+
+impl PendulumApp {
+    /// The default app with the layout restored from the last run, if any.
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self::default();
+        if let Some(layout) = cc.storage.and_then(|s| eframe::get_value::<LayoutPrefs>(s, LAYOUT_KEY)) {
+            app.layout = layout.clamped();
+        }
+        app
+    }
+}
+
+impl Default for PendulumApp {
+    fn default() -> Self {
+        let presets = load_presets(Path::new(PRESETS_FILE)).unwrap_or_else(|_| builtin_presets());
+
+        Self {
+            sim: Simulation::default(),
             running: false,
-            start_instant: Instant::now(),
+            sim_time: 0.0,
             last_update: Instant::now(),
             simulation_speed: 1.0,
-            theta: 0.35,
-            omega: 0.0,
+            fixed_dt_enabled: false,
+            fixed_dt: 1.0 / 60.0,
+            settle: None,
+            settle_seconds: 3.0,
+            speed_changed_at: None,
+            reversal: None,
+            reversal_error: None,
             initial_theta: 0.35,
+            initial_omega: 0.0,
+            kick_impulse: 1.0,
+            escapement_enabled: false,
+            escapement: 0.05,
+            theta_unwrapped: 0.35,
             history: VecDeque::with_capacity(4096),
             history_capacity: 4096,
             sample_accum: 0.0,
             sample_dt: 1.0 / 60.0,
+            step_history: VecDeque::with_capacity(4096),
+            substeps_since_sample: 0,
+            energy_history: VecDeque::with_capacity(4096),
+            compare: None,
+            compare_history: VecDeque::with_capacity(4096),
+            compare_energy_history: VecDeque::with_capacity(4096),
+            energy_reference: None,
             plot_seconds: 10.0,
+            plot_view: None,
             show_timeline: true,
             selected_plot: PlotKind::Angle,
+            auto_fit_y: HashSet::new(),
+            show_small_angle: false,
+            time_axis: TimeAxis::Relative,
+            decimate_plots: true,
+            layout: LayoutPrefs::default(),
             dark_mode: true,
+            high_contrast: false,
+            reduce_motion: false,
             auto_reset_history: true,
             show_help: false,
+            show_debug: false,
+            last_dt: 0.0,
+            last_substeps: 0,
+            show_revolutions: false,
+            bob_scale: BobScale::default(),
+            show_forces: false,
+            integrator: Integrator::Rk4,
+            elastic: false,
+            spring_stiffness: 40.0,
+            radius: 1.0,
+            radius_rate: 0.0,
+            rk45_tolerance: 1e-5,
+            adaptive_dt: 0.005,
+            drive_enabled: false,
+            drive_amplitude: 0.5,
+            drive_frequency: 3.0,
+            drive_time: 0.0,
+            poincare: VecDeque::with_capacity(POINCARE_CAPACITY),
+            bifurcation: BifurcationSettings { from: 5.0, to: 15.0, ..BifurcationSettings::default() },
+            bifurcation_points: Vec::new(),
+            preview_params: false,
+            preview: None,
+            period_target: 2.0,
+            period_match: None,
+            period_sweep: Vec::new(),
+            race: None,
+            export_path: "history.npy".into(),
+            csv_path: "history.csv".into(),
+            state_path: "pendulum_state.json".into(),
+            export_status: None,
+            presets_path: PRESETS_FILE.into(),
+            new_preset_name: String::new(),
+            preset_name_error: None,
+            capture_dir: "capture".into(),
+            capture_frames: 120,
+            capture_fps: 30.0,
+            sequence: None,
+            sequence_request: None,
+            png_path: "figure.png".into(),
+            png_target: PngTarget::Pendulum,
+            png_pending: false,
+            png_request: None,
+            canvas_rect: Rect::NOTHING,
+            plot_rect: Rect::NOTHING,
+            replay_path: "history.csv".into(),
+            replay: None,
+            recording: false,
+            recorded: VecDeque::new(),
+            share_text: String::new(),
             frame_count: 0,
             last_fps_update: Instant::now(),
             current_fps: 0.0,
@@ -128,43 +537,1040 @@ impl PendulumApp {
     fn push_history(&mut self, t: f32) {
         if self.auto_reset_history && self.history.len() == self.history_capacity {
             self.history.pop_front();
+            self.energy_history.pop_front();
         }
         self.history
-            .push_back((t, self.theta.to_degrees(), self.omega.to_degrees()));
+            .push_back((t, self.sim.theta.to_degrees(), self.sim.omega.to_degrees()));
+        let (potential, kinetic, _) = self.calculate_energy();
+        self.energy_history.push_back((t, potential, kinetic));
+        if let Some(c) = &self.compare {
+            if self.auto_reset_history && self.compare_history.len() == self.history_capacity {
+                self.compare_history.pop_front();
+                self.compare_energy_history.pop_front();
+            }
+            self.compare_history
+                .push_back((t, c.theta.to_degrees(), c.omega.to_degrees()));
+            let (potential, kinetic) = energy(c.theta, c.omega, c.length, c.mass, self.sim.gravity);
+            self.compare_energy_history.push_back((t, potential, kinetic));
+        }
+    }
+
+    /// Keep at most `capacity` samples per history, dropping the oldest.
+    fn set_history_capacity(&mut self, capacity: usize) {
+        self.history_capacity = capacity;
+        for h in [
+            &mut self.history,
+            &mut self.energy_history,
+            &mut self.compare_history,
+            &mut self.compare_energy_history,
+            &mut self.step_history,
+        ] {
+            if h.len() > capacity {
+                h.drain(..h.len() - capacity);
+            }
+            h.reserve(capacity - h.len());
+        }
+    }
+
+    fn clear_history(&mut self) {
+        // a window pinned in the old run's time would show nothing
+        if let Some(view) = &mut self.plot_view {
+            view.end = None;
+        }
+        self.history.clear();
+        self.energy_history.clear();
+        self.step_history.clear();
+        self.poincare.clear();
+        self.compare_history.clear();
+        self.compare_energy_history.clear();
+    }
+
+    /// Put pendulum B back at its initial angle, at rest.
+    fn restart_compare(&mut self) {
+        if let Some(c) = &mut self.compare {
+            c.theta = c.initial_theta;
+            c.omega = 0.0;
+        }
+    }
+
+    /// Turn pendulum B on (copying A with a longer rod, so the period
+    /// difference shows straight away) or off.
+    fn set_compare(&mut self, enabled: bool) {
+        self.compare = enabled.then(|| ComparePendulum {
+            length: (self.sim.length * 1.5).min(10.0),
+            mass: self.sim.mass,
+            drag: self.sim.drag,
+            initial_theta: self.initial_theta,
+            theta: self.sim.theta,
+            omega: self.sim.omega,
+        });
+        self.compare_history.clear();
+        self.compare_energy_history.clear();
+    }
+
+    /// Restart the drive at phase zero, dropping the Poincaré section it built.
+    fn reset_drive(&mut self) {
+        self.drive_time = 0.0;
+        self.poincare.clear();
     }
 
     pub fn apply_preset(&mut self, idx: usize) {
         if let Some(p) = self.presets.get(idx) {
-            self.length = p.length;
-            self.mass = p.mass;
-            self.drag = p.drag;
-            self.gravity = p.gravity;
+            self.sim.length = p.length;
+            self.sim.mass = p.mass;
+            self.sim.drag = p.drag;
+            self.sim.gravity = p.gravity;
             self.initial_theta = p.initial_angle.to_radians();
-            self.theta = self.initial_theta;
-            self.omega = 0.0;
+            self.initial_omega = p.initial_omega.to_radians();
+            self.sim.theta = self.initial_theta;
+            self.radius = self.sim.length;
+            self.radius_rate = 0.0;
+            self.theta_unwrapped = self.sim.theta;
+            self.reset_drive();
+            self.restart_compare();
+            self.energy_reference = None;
+            self.sim.omega = self.initial_omega;
             self.current_preset = idx;
+            self.restart_race();
+            // a round trip is timed from here, like after Reset
+            self.sim_time = 0.0;
+            self.reversal = None;
+            self.reversal_error = None;
             if self.auto_reset_history {
-                self.history.clear();
+                self.clear_history();
+            }
+        }
+    }
+
+    pub fn palette(&self) -> &'static Palette {
+        Palette::get(self.high_contrast)
+    }
+
+    /// Delay between animation frames; about 10 fps with reduced motion.
+    fn repaint_interval(&self) -> Duration {
+        Duration::from_millis(if self.reduce_motion { 100 } else { 16 })
+    }
+
+    pub fn spring(&self) -> Spring {
+        Spring { stiffness: self.spring_stiffness.max(0.1), rest_length: self.sim.length.max(0.01) }
+    }
+
+    /// Current rod length: the spring's stretched length in elastic mode.
+    pub fn rod_length(&self) -> f32 {
+        if self.elastic { self.radius } else { self.sim.length }
+    }
+
+    pub fn calculate_energy(&self) -> (f32, f32, f32) {
+        let (potential, kinetic) = if self.elastic {
+            let s = &self.sim;
+            elastic_energy(self.radius, self.radius_rate, s.theta, s.omega, s.mass, s.gravity, self.spring())
+        } else {
+            energy(self.sim.theta, self.sim.omega, self.sim.length, self.sim.mass, self.sim.gravity)
+        };
+        (potential, kinetic, potential + kinetic)
+    }
+
+    /// Oscillation period measured from upward zero crossings of θ in the
+    /// recorded history, averaged over the most recent few swings. Damping
+    /// shrinks the amplitude but not the crossing spacing, so this stays valid
+    /// until the swing dies out. Jumps in time are skipped.
+    pub fn measured_period(&self) -> Option<f32> {
+        const MAX_CROSSINGS: usize = 8;
+        const MAX_GAP: f32 = 0.25;
+        let mut crossings: Vec<f32> = Vec::with_capacity(MAX_CROSSINGS);
+        let mut intervals = 0.0;
+        let mut count = 0;
+        for ((t0, th0, _), (t1, th1, w1)) in self.history.iter().rev().skip(1).zip(self.history.iter().rev()) {
+            if !(0.0..=MAX_GAP).contains(&(t1 - t0)) {
+                // a jump in time (history kept across a reset) breaks the
+                // sequence; only keep crossings after it
+                break;
             }
+            if *th0 < 0.0 && *th1 >= 0.0 && *w1 > 0.5 {
+                let t = t0 + (t1 - t0) * (-th0 / (th1 - th0));
+                if let Some(&later) = crossings.last() {
+                    intervals += later - t;
+                    count += 1;
+                }
+                crossings.push(t);
+                if crossings.len() == MAX_CROSSINGS {
+                    break;
+                }
+            }
+        }
+        (count > 0).then(|| intervals / count as f32)
+    }
+
+    /// Snapshot the current parameters as a preset called `name`.
+    fn preset_from_current(&self, name: String) -> Preset {
+        Preset {
+            name,
+            length: self.sim.length,
+            mass: self.sim.mass,
+            drag: self.sim.drag,
+            gravity: self.sim.gravity,
+            initial_angle: self.initial_theta.to_degrees(),
+            initial_omega: self.initial_omega.to_degrees(),
+        }
+    }
+
+    /// `base`, or `base (2)`, `base (3)`, … if that name is already taken.
+    fn unique_preset_name(&self, base: &str) -> String {
+        let taken = |name: &str| self.presets.iter().any(|p| p.name == name);
+        if !taken(base) {
+            return base.to_owned();
+        }
+        (2..)
+            .map(|i| format!("{} ({})", base, i))
+            .find(|name| !taken(name))
+            .expect("unbounded suffix search")
+    }
+
+    /// Add the current parameters as a preset named after `new_preset_name`
+    /// and select it. Empty names are rejected.
+    fn add_preset_from_current(&mut self) {
+        let base = self.new_preset_name.trim();
+        if base.is_empty() {
+            self.preset_name_error = Some("Enter a name for the preset".into());
+            return;
+        }
+        let name = self.unique_preset_name(base);
+        self.presets.push(self.preset_from_current(name));
+        self.current_preset = self.presets.len() - 1;
+        self.new_preset_name.clear();
+        self.preset_name_error = None;
+    }
+
+    pub fn share_config(&self) -> ShareConfig {
+        ShareConfig {
+            version: SHARE_VERSION,
+            length: self.sim.length,
+            mass: self.sim.mass,
+            drag: self.sim.drag,
+            gravity: self.sim.gravity,
+            initial_theta: self.initial_theta,
+            initial_omega: self.initial_omega,
+            simulation_speed: self.simulation_speed,
+            integrator: self.integrator,
+            drag_model: self.sim.drag_model,
+            pivot_friction: self.sim.pivot_friction,
+            drive_enabled: self.drive_enabled,
+            drive_amplitude: self.drive_amplitude,
+            drive_frequency: self.drive_frequency,
+            elastic: self.elastic,
+            spring_stiffness: self.spring_stiffness,
+        }
+    }
+
+    /// Load a shared setup and restart from its initial condition.
+    pub fn apply_share_config(&mut self, c: &ShareConfig) {
+        self.sim.length = c.length;
+        self.sim.mass = c.mass;
+        self.sim.drag = c.drag;
+        self.sim.gravity = c.gravity;
+        // the same limits as the controls, whatever the string carried
+        self.initial_theta = c.initial_theta.clamp(-179f32.to_radians(), 179f32.to_radians());
+        self.initial_omega = c.initial_omega.clamp(-2000f32.to_radians(), 2000f32.to_radians());
+        self.simulation_speed = c.simulation_speed.clamp(0.1, 5.0);
+        self.integrator = c.integrator;
+        self.sim.drag_model = c.drag_model;
+        self.sim.pivot_friction = c.pivot_friction.clamp(0.0, 5.0);
+        self.drive_enabled = c.drive_enabled;
+        // strings without a drive or spring leave those sliders where they are
+        if c.drive_enabled {
+            self.drive_amplitude = c.drive_amplitude.clamp(-DRIVE_AMPLITUDE_MAX, DRIVE_AMPLITUDE_MAX);
+            self.drive_frequency = c.drive_frequency.clamp(0.0, DRIVE_FREQUENCY_MAX);
+        }
+        self.elastic = c.elastic;
+        if c.elastic {
+            self.spring_stiffness = c.spring_stiffness.clamp(1.0, 500.0);
+        }
+        self.sim.theta = self.initial_theta;
+        self.theta_unwrapped = self.sim.theta;
+        self.reset_drive();
+        self.restart_compare();
+        self.energy_reference = None;
+        self.sim.omega = self.initial_omega;
+        self.clamp_parameters();
+        self.radius = self.sim.length;
+        self.radius_rate = 0.0;
+        self.restart_race();
+        self.sim_time = 0.0;
+        self.reversal = None;
+        self.reversal_error = None;
+        if self.auto_reset_history {
+            self.clear_history();
+        }
+    }
+
+    /// Re-take the energy reference whenever the parameters change, and drop
+    /// it while drag, the drive, a settle or a replay make energy vary anyway.
+    fn track_energy_reference(&mut self) {
+        let conserving =
+            self.sim.drag == 0.0
+                && self.sim.pivot_friction == 0.0
+                && !self.drive_enabled
+                && !self.escapement_enabled && self.settle.is_none() && self.replay.is_none();
+        let stiffness = if self.elastic { self.spring_stiffness } else { 0.0 };
+        let params = (self.sim.length, self.sim.mass, self.sim.gravity, stiffness);
+        if !conserving {
+            self.energy_reference = None;
+        } else if self.energy_reference.is_none_or(|(_, p)| p != params) {
+            self.energy_reference = Some((self.calculate_energy().2, params));
+        }
+    }
+
+    /// Relative drift of total energy from the reference, e.g. 0.05 for 5%.
+    pub fn energy_drift(&self) -> Option<f32> {
+        let (reference, _) = self.energy_reference?;
+        (reference > 1e-6).then(|| (self.calculate_energy().2 - reference) / reference)
+    }
+
+    fn toggle_running(&mut self) {
+        self.running = !self.running;
+        self.last_update = Instant::now();
+        if self.running {
+            self.push_history(self.sim_time);
+        }
+    }
+
+    /// Restart from the initial angle and angular velocity.
+    fn reset(&mut self) {
+        self.sim.theta = self.initial_theta;
+        self.radius = self.sim.length;
+        self.radius_rate = 0.0;
+        self.theta_unwrapped = self.sim.theta;
+        self.reset_drive();
+        self.restart_compare();
+        self.energy_reference = None;
+        self.sim.omega = self.initial_omega;
+        if self.auto_reset_history {
+            self.clear_history();
+        }
+        self.sim_time = 0.0;
+        self.reversal = None;
+        self.restart_race();
+    }
+
+    /// Start a running integrator race over from the current state.
+    fn restart_race(&mut self) {
+        if self.race.is_some() {
+            self.race = Some(IntegratorRace::start(self.sim.theta, self.sim.omega));
+        }
+    }
+
+    fn run_state(&self) -> RunState {
+        RunState {
+            sim: self.sim,
+            initial_theta: self.initial_theta,
+            initial_omega: self.initial_omega,
+            time: self.sim_time,
+            integrator: self.integrator,
+            rk45_tolerance: self.rk45_tolerance,
+            drive_enabled: self.drive_enabled,
+            drive_amplitude: self.drive_amplitude,
+            drive_frequency: self.drive_frequency,
+            drive_time: self.drive_time,
+            elastic: self.elastic,
+            spring_stiffness: self.spring_stiffness,
+            radius: self.radius,
+            radius_rate: self.radius_rate,
+            escapement_enabled: self.escapement_enabled,
+            escapement: self.escapement,
+        }
+    }
+
+    /// Continue from a saved run: its parameters, integrator, drive, spring,
+    /// escapement, state and clocks, with the history started afresh. A
+    /// switched-off drive or spring keeps the sliders where they are.
+    fn apply_run_state(&mut self, s: &RunState) {
+        self.sim = s.sim;
+        self.initial_theta = s.initial_theta;
+        self.initial_omega = s.initial_omega;
+        self.integrator = s.integrator;
+        self.rk45_tolerance = s.rk45_tolerance;
+        self.drive_enabled = s.drive_enabled;
+        if s.drive_enabled {
+            self.drive_amplitude = s.drive_amplitude;
+            self.drive_frequency = s.drive_frequency;
+        }
+        self.elastic = s.elastic;
+        if s.elastic {
+            self.spring_stiffness = s.spring_stiffness;
+            self.radius = s.radius;
+            self.radius_rate = s.radius_rate;
+        } else {
+            self.radius = self.sim.length;
+            self.radius_rate = 0.0;
+        }
+        self.escapement_enabled = s.escapement_enabled;
+        if s.escapement_enabled {
+            self.escapement = s.escapement;
+        }
+        self.theta_unwrapped = self.sim.theta;
+        self.restart_compare();
+        self.energy_reference = None;
+        self.clear_history();
+        self.sim_time = s.time;
+        self.drive_time = s.drive_time;
+        self.reversal = None;
+        self.reversal_error = None;
+        self.restart_race();
+        self.push_history(self.sim_time);
+    }
+
+    /// Stop dead hanging straight down (θ = 0, ω = 0) and start a fresh
+    /// history, without touching the initial angle that Reset returns to.
+    /// Pendulum B and the integrator race start over with it.
+    fn hang(&mut self) {
+        self.sim.theta = 0.0;
+        self.sim.omega = 0.0;
+        self.radius = self.sim.length;
+        self.radius_rate = 0.0;
+        self.theta_unwrapped = 0.0;
+        self.settle = None;
+        self.reset_drive();
+        self.restart_compare();
+        self.energy_reference = None;
+        self.clear_history();
+        self.sim_time = 0.0;
+        self.reversal = None;
+        self.restart_race();
+    }
+
+    /// Negate every velocity so the motion retraces itself, then pause after
+    /// running as long as the sim had run since the last reset. A reversible
+    /// integrator at zero drag ends up back at the initial condition; the
+    /// leftover difference is stored in `reversal_error`. Pressing again
+    /// mid-way turns back round and cancels the check.
+    fn reverse(&mut self) {
+        self.sim.omega = -self.sim.omega;
+        self.radius_rate = -self.radius_rate;
+        if let Some(c) = &mut self.compare {
+            c.omega = -c.omega;
+        }
+        self.reversal = match self.reversal {
+            Some(_) => None,
+            None => (self.sim_time > 0.0).then_some(self.sim_time),
+        };
+        self.reversal_error = None;
+        self.running = true;
+        self.last_update = Instant::now();
+    }
+
+    /// Count a substep against a pending reversal and pause once it completes.
+    fn advance_reversal(&mut self, step: f32) {
+        if let Some(left) = &mut self.reversal {
+            *left -= step;
+            if *left <= 1e-6 {
+                self.reversal = None;
+                self.running = false;
+                self.reversal_error = Some((
+                    wrap_angle(self.sim.theta - self.initial_theta),
+                    self.sim.omega + self.initial_omega,
+                ));
+            }
+        }
+    }
+
+    /// Add `kick_impulse` to ω at once, like a push on the bob. Runs between
+    /// frames, so it never lands inside an integration step.
+    fn kick(&mut self) {
+        if self.replay.is_some() {
+            return;
+        }
+        self.sim.omega += self.kick_impulse;
+        // energy changed on purpose; take a fresh reference for the drift readout
+        self.energy_reference = None;
+    }
+
+    /// Advance a paused sim by exactly one history sample.
+    fn step_once(&mut self) {
+        if !self.running && self.replay.is_none() {
+            self.integrate(self.sample_dt);
+        }
+    }
+
+    /// Space: start/pause, R: reset, C: clear data, →: single step while
+    /// paused, K: kick. Ignored while a text field has keyboard focus.
+    fn handle_shortcuts(&mut self, ctx: &Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (space, reset, clear, step, kick) = ctx.input(|i| {
+            (
+                i.key_pressed(egui::Key::Space),
+                i.key_pressed(egui::Key::R),
+                i.key_pressed(egui::Key::C),
+                i.key_pressed(egui::Key::ArrowRight),
+                i.key_pressed(egui::Key::K),
+            )
+        });
+        if space {
+            self.toggle_running();
+        }
+        if reset {
+            self.reset();
+        }
+        if clear {
+            self.clear_history();
+        }
+        if step {
+            self.step_once();
+        }
+        if kick {
+            self.kick();
+        }
+    }
+
+    /// Bring the pendulum to rest at the bottom over `duration` seconds by
+    /// ramping in extra damping on top of `drag`, then removing it again.
+    /// Unlike Reset this animates to rest instead of snapping.
+    pub fn settle(&mut self, duration: f32) {
+        self.settle = Some((0.0, duration.max(0.1)));
+        self.running = true;
+        self.last_update = Instant::now();
+    }
+
+    /// Extra damping coefficient for a substep of `step` seconds during a `settle`.
+    fn settle_damping(&self, step: f32, l: f32, m: f32, g: f32) -> f32 {
+        let Some((elapsed, duration)) = self.settle else {
+            return 0.0;
+        };
+        let p = (elapsed + step) / duration;
+        if p >= 1.0 {
+            return 0.0;
+        }
+        // smooth ramp in over the first quarter, out over the last fifth
+        let ramp_in = (p / 0.25).min(1.0);
+        let ramp_out = ((1.0 - p) / 0.2).min(1.0);
+        let envelope = ramp_in * ramp_in * (3.0 - 2.0 * ramp_in) * ramp_out;
+        // critical damping of the linearised pendulum: fastest non-oscillating decay
+        2.0 * m * (g / l).sqrt() * envelope
+    }
+
+    /// Drive torque currently acting, or `Drive::NONE` when driving is off.
+    fn drive(&self) -> Drive {
+        if self.drive_enabled {
+            Drive { amplitude: self.drive_amplitude, frequency: self.drive_frequency }
+        } else {
+            Drive::NONE
+        }
+    }
+
+    /// Move a running `settle` forward by an accepted substep.
+    fn advance_settle(&mut self, step: f32) {
+        if let Some((elapsed, duration)) = &mut self.settle {
+            *elapsed += step;
+            if *elapsed >= *duration {
+                self.settle = None;
+            }
+        }
+    }
+
+    /// Advance the sim by `dt` seconds with the selected integrator, sampling
+    /// history. RK45 grows or shrinks its substep to keep the local error under
+    /// `rk45_tolerance`; the others use fixed 5 ms substeps.
+    pub fn integrate(&mut self, dt: f32) {
+        let params = Params { drive: self.drive(), ..self.sim.params() };
+        let Params { l, m, drag, g, drive } = params;
+
+        // stop exactly at the end of a round trip
+        let mut remaining = self.reversal.map_or(dt, |left| dt.min(left));
+        if let Some(race) = &mut self.race {
+            race.advance(remaining, params, self.history_capacity);
+        }
+        let max_sub = 0.005_f32;
+        self.last_dt = remaining;
+        self.last_substeps = 0;
+        while remaining > 0.0 {
+            let step;
+            let (th, w);
+            if self.elastic {
+                // fixed substeps; the adaptive integrators only model the rigid rod
+                step = remaining.min(max_sub);
+                let extra = self.settle_damping(step, l, m, g);
+                (self.radius, self.radius_rate, th, w) = rk4_step_elastic(
+                    (self.radius, self.radius_rate, self.sim.theta, self.sim.omega),
+                    step,
+                    Params { drag: Drag { linear: drag.linear + extra, ..drag }, ..params },
+                    self.spring(),
+                    self.drive_time,
+                );
+                self.adaptive_dt = step;
+            } else if self.integrator == Integrator::Rk45 {
+                // never step past a history sample, so plots keep their resolution
+                let max_step = self.sample_dt.min(remaining);
+                let tol = self.rk45_tolerance.max(1e-7);
+                (th, w, step, self.adaptive_dt) = rk45_adaptive_step(
+                    self.sim.theta,
+                    self.sim.omega,
+                    self.drive_time,
+                    self.adaptive_dt,
+                    max_step,
+                    tol,
+                    |h| {
+                        let extra = self.settle_damping(h, l, m, g);
+                        Params { drag: Drag { linear: drag.linear + extra, ..drag }, ..params }
+                    },
+                );
+            } else {
+                step = remaining.min(max_sub);
+                let extra = self.settle_damping(step, l, m, g);
+                (th, w) = self.integrator.step(
+                    self.sim.theta,
+                    self.sim.omega,
+                    step,
+                    Params { drag: Drag { linear: drag.linear + extra, ..drag }, ..params },
+                    self.drive_time,
+                );
+                self.adaptive_dt = step;
+            }
+            if let Some(c) = &mut self.compare {
+                let c_params = Params {
+                    l: c.length.max(0.01),
+                    m: c.mass.max(1e-6),
+                    // same model and pivot friction as A, so only the parameters differ
+                    drag: self.sim.drag_terms_for(c.drag),
+                    ..params
+                };
+                (c.theta, c.omega) = self.integrator.step(c.theta, c.omega, step, c_params, self.drive_time);
+                c.theta = wrap_angle(c.theta);
+            }
+            self.advance_settle(step);
+            let cycles_before = drive.frequency * self.drive_time / std::f32::consts::TAU;
+            self.drive_time += step;
+            self.sim_time += step;
+            if drive.amplitude != 0.0 && drive.frequency > 0.0 {
+                // the same phase `Drive::accel` uses, so the section stays at
+                // one drive phase across toggles and frequency changes
+                let cycles_after = drive.frequency * self.drive_time / std::f32::consts::TAU;
+                if cycles_after.floor() > cycles_before.floor() {
+                    // interpolate to the instant within the step where a cycle completed
+                    let u = ((cycles_after.floor() - cycles_before) / (cycles_after - cycles_before)).clamp(0.0, 1.0);
+                    if self.poincare.len() == POINCARE_CAPACITY {
+                        self.poincare.pop_front();
+                    }
+                    self.poincare.push_back((
+                        wrap_angle(self.sim.theta + (th - self.sim.theta) * u),
+                        self.sim.omega + (w - self.sim.omega) * u,
+                    ));
+                }
+            }
+            self.theta_unwrapped += th - self.sim.theta;
+            // a sign change near the bottom (not the wrap at the top) is a pass
+            // through θ = 0, where the escapement gives its push
+            let w = if self.escapement_enabled
+                && self.sim.theta.abs() < 1.0
+                && th.abs() < 1.0
+                && (self.sim.theta < 0.0) != (th < 0.0)
+            {
+                w + self.escapement.copysign(w)
+            } else {
+                w
+            };
+            // keep θ in [-π, π] so going over the top wraps instead of growing;
+            // ω is untouched, so the energy is unchanged
+            self.sim.theta = wrap_angle(th);
+            self.sim.omega = w;
+            remaining -= step;
+            self.substeps_since_sample += 1;
+            self.last_substeps += 1;
+            self.advance_reversal(step);
+
+            self.sample_accum += step;
+            if self.sample_accum >= self.sample_dt {
+                let t = self.sim_time;
+                self.push_history(t);
+                if self.recording {
+                    if self.recorded.len() == RECORD_CAPACITY {
+                        self.recorded.pop_front();
+                    }
+                    self.recorded
+                        .push_back((t, self.sim.theta.to_degrees(), self.sim.omega.to_degrees()));
+                }
+                if self.step_history.len() == self.history_capacity {
+                    self.step_history.pop_front();
+                }
+                self.step_history.push_back((
+                    t,
+                    step * 1000.0,
+                    self.substeps_since_sample as f32,
+                ));
+                self.substeps_since_sample = 0;
+                self.sample_accum -= self.sample_dt;
+            }
+        }
+    }
+
+    /// Draw the pendulum canvas and handle its input. While paused, dragging
+    /// on the canvas swings the bob to the pointer and makes that the new
+    /// initial angle, at rest.
+    fn pendulum_canvas(&mut self, ctx: &Context, painter: &Painter, resp: &Response) {
+        self.canvas_rect = resp.rect;
+        if !self.running
+            && self.replay.is_none()
+            && let Some(angle) = dragged_angle(resp)
+        {
+            self.initial_theta = angle.clamp(-179f32.to_radians(), 179f32.to_radians());
+            self.sim.theta = self.initial_theta;
+            self.theta_unwrapped = self.sim.theta;
+            self.sim.omega = 0.0;
+            self.radius = self.sim.length;
+            self.radius_rate = 0.0;
+            self.reset_drive();
+            self.restart_compare();
+            self.restart_race();
+            self.energy_reference = None;
+        }
+        draw_pendulum(self, painter, resp.rect);
+        self.canvas_speed_input(ctx, painter, resp);
+    }
+
+    /// Ctrl+scroll (or pinch) over the pendulum canvas scales the simulation
+    /// speed. Only the canvas reacts, so scrolling elsewhere is unaffected.
+    fn canvas_speed_input(&mut self, ctx: &Context, painter: &Painter, resp: &Response) {
+        if resp.hovered() {
+            let zoom = ctx.input(|i| i.zoom_delta());
+            if zoom != 1.0 {
+                self.simulation_speed = (self.simulation_speed * zoom).clamp(0.1, 5.0);
+                self.speed_changed_at = Some(Instant::now());
+            }
+        }
+        if let Some(at) = self.speed_changed_at {
+            let age = at.elapsed().as_secs_f32();
+            if age > 1.5 {
+                self.speed_changed_at = None;
+            } else {
+                let alpha = (255.0 * (1.0 - age / 1.5)) as u8;
+                painter.text(
+                    egui::pos2(resp.rect.center().x, resp.rect.bottom() - 16.0),
+                    egui::Align2::CENTER_BOTTOM,
+                    format!("Speed ×{:.2}", self.simulation_speed),
+                    egui::FontId::proportional(18.0),
+                    egui::Color32::from_rgba_unmultiplied(255, 255, 255, alpha),
+                );
+                ctx.request_repaint();
+            }
+        }
+    }
+
+    fn draw_selected_plot(&mut self, painter: &Painter, resp: &Response) {
+        self.plot_rect = resp.rect;
+        let (rect, hover) = (resp.rect, resp.hover_pos());
+        let palette = self.palette();
+        let g = self.sim.gravity;
+        let (l, m) = (self.sim.length, self.sim.mass);
+        let total_energy = |&(_, p, k): &(f32, f32, f32)| p + k;
+        match self.selected_plot {
+            PlotKind::Angle => {
+                let range = if self.auto_fit_y.contains(&PlotKind::Angle) {
+                    self.compare.and_then(|_| self.combined_range(|(_, th, _)| *th, |(_, th, _)| *th))
+                } else {
+                    Some((-180.0, 180.0))
+                };
+                let legend = self.compare_legend(palette.angle);
+                let options = SeriesOptions {
+                    wraps: true,
+                    fixed_range: range,
+                    legend: &legend,
+                    ..self.series_options("Angle (°)", palette.angle, hover)
+                };
+                let axes = draw_time_series(painter, rect, palette, &self.history, |(_, th, _)| *th, options);
+                self.overlay_compare(painter, rect, axes, |(_, th, _)| *th);
+                if let (Some(axes), true) = (axes, self.show_small_angle) {
+                    // history time is seconds since reset, so t = 0 is the initial condition
+                    let (theta0, omega0) = (self.initial_theta, self.initial_omega);
+                    let b = self.sim.drag_terms().linear;
+                    let theta = |t: f32| small_angle_theta(theta0, omega0, l, m, b, g, t).to_degrees();
+                    draw_function_overlay(painter, rect, palette, axes, theta, palette.title);
+                }
+            }
+            PlotKind::Velocity => {
+                // with B shown, scale to both traces so neither is clipped
+                let range = self
+                    .compare
+                    .and_then(|_| self.combined_range(|(_, _, w)| *w, |(_, _, w)| *w));
+                let legend = self.compare_legend(palette.velocity);
+                let options = SeriesOptions {
+                    fixed_range: range,
+                    legend: &legend,
+                    ..self.series_options("Angular Velocity (°/s)", palette.velocity, hover)
+                };
+                let axes = draw_time_series(painter, rect, palette, &self.history, |(_, _, w)| *w, options);
+                self.overlay_compare(painter, rect, axes, |(_, _, w)| *w);
+            }
+            PlotKind::Energy => {
+                let range = if self.auto_fit_y.contains(&PlotKind::Energy) {
+                    self.compare.and_then(|_| {
+                        self.combined_range_of(&self.energy_history, &self.compare_energy_history, total_energy, total_energy)
+                    })
+                } else {
+                    let peak = self
+                        .energy_history
+                        .iter()
+                        .chain(&self.compare_energy_history)
+                        .map(total_energy)
+                        .fold(1e-3, f32::max);
+                    // anchor at zero so a conserved energy reads as a flat line, not amplified noise
+                    Some((0.0, peak * 1.15))
+                };
+                let legend = self.compare_legend(palette.energy);
+                let options = SeriesOptions {
+                    fixed_range: range,
+                    legend: &legend,
+                    ..self.series_options("Total energy (J)", palette.energy, hover)
+                };
+                let axes = draw_time_series(painter, rect, palette, &self.energy_history, total_energy, options);
+                if let (Some(axes), Some(_)) = (axes, &self.compare) {
+                    let series = &self.compare_energy_history;
+                    draw_series_overlay(painter, rect, palette, axes, series, total_energy, palette.compare);
+                }
+            }
+            PlotKind::Phase => draw_phase_plot(painter, rect, palette, &self.history),
+            PlotKind::StepSize => {
+                let options = self.series_options("Integrator step (ms)", palette.step, hover);
+                draw_time_series(painter, rect, palette, &self.step_history, |(_, dt_ms, _)| *dt_ms, options);
+            }
+            PlotKind::Poincare => draw_poincare_plot(painter, rect, palette, &self.poincare),
+            PlotKind::Lissajous => draw_lissajous_plot(painter, rect, palette, &self.history, &self.compare_history),
+            PlotKind::PeriodSweep => {
+                let t0 = std::f32::consts::TAU * (l / g).sqrt();
+                draw_period_sweep(painter, rect, palette, &self.period_sweep, t0);
+            }
+            PlotKind::Integrators => match &self.race {
+                Some(race) => {
+                    let colors = [palette.angle, palette.energy, palette.compare];
+                    let legend: Vec<(&str, egui::Color32)> =
+                        IntegratorRace::METHODS.iter().zip(colors).map(|((name, _), c)| (*name, c)).collect();
+                    let options = SeriesOptions {
+                        wraps: true,
+                        fixed_range: Some((-180.0, 180.0)),
+                        legend: &legend,
+                        ..self.series_options("Angle by integrator (°)", colors[0], hover)
+                    };
+                    let axes = draw_time_series(painter, rect, palette, &race.histories[0], |(_, th, _)| *th, options);
+                    if let Some(axes) = axes {
+                        for (history, color) in race.histories.iter().zip(colors).skip(1) {
+                            draw_series_overlay(painter, rect, palette, axes, history, |(_, th, _)| *th, color);
+                        }
+                    }
+                }
+                None => {
+                    let options =
+                        SeriesOptions { wraps: true, ..self.series_options("Angle by integrator (°)", palette.angle, hover) };
+                    draw_time_series(painter, rect, palette, &VecDeque::new(), |(_, th, _)| *th, options);
+                }
+            },
+            PlotKind::Bifurcation => draw_bifurcation(
+                painter,
+                rect,
+                palette,
+                &self.bifurcation_points,
+                (self.bifurcation.from, self.bifurcation.to),
+            ),
+            PlotKind::Spectrum => {
+                let min_t = self.history.back().map_or(0.0, |e| e.0) - self.plot_seconds;
+                let theta: Vec<(f32, f32)> = self
+                    .history
+                    .iter()
+                    .filter(|e| e.0 >= min_t)
+                    .map(|(t, th, _)| (*t, *th))
+                    .collect();
+                let signal = resample_uniform(&theta, self.sample_dt);
+                draw_spectrum(
+                    painter,
+                    rect,
+                    palette,
+                    &magnitude_spectrum(&signal, self.sample_dt),
+                    "Angle spectrum (°)",
+                    palette.spectrum,
+                );
+            }
+        }
+        if self.plot_view.is_some() && self.selected_plot.is_time_series() {
+            painter.text(
+                rect.right_top() + egui::vec2(-8.0, 6.0),
+                egui::Align2::RIGHT_TOP,
+                "custom view · double-click to follow live",
+                egui::FontId::proportional(11.0),
+                egui::Color32::from_gray(170),
+            );
+        }
+    }
+
+    fn time_view(&self) -> TimeView {
+        self.plot_view.unwrap_or(TimeView::live(self.plot_seconds))
+    }
+
+    /// Scroll (or pinch) over a time series zooms around the pointer, drag
+    /// pans back through history and double-click returns to live follow.
+    fn plot_view_input(&mut self, ctx: &Context, resp: &Response) {
+        if resp.double_clicked() {
+            self.plot_view = None;
+            return;
+        }
+        let view = self.time_view();
+        let newest = self.history.back().map_or(0.0, |e| e.0);
+        let (_, mut end) = view.window(newest);
+        let mut span = view.span;
+        if resp.hovered() {
+            let (scroll, zoom) = ctx.input(|i| (i.scroll_delta.y, i.zoom_delta()));
+            let factor = (-scroll * 0.002).exp() / zoom;
+            if factor != 1.0
+                && let Some(pos) = resp.hover_pos()
+            {
+                // keep the time under the pointer fixed while the span changes
+                let from_right = (resp.rect.right() - pos.x) / resp.rect.width();
+                let new_span = (span * factor).clamp(0.5, 600.0);
+                end -= from_right * (span - new_span);
+                span = new_span;
+            }
+        }
+        if resp.dragged() {
+            end -= resp.drag_delta().x / resp.rect.width() * span;
+        }
+        if (end, span) != (view.window(newest).1, view.span) {
+            let first = self.history.front().map_or(newest, |e| e.0);
+            let end = end.clamp(first, newest);
+            // pinned in absolute time until brought back to the newest sample
+            self.plot_view = Some(TimeView { end: (end < newest).then_some(end), span });
+        }
+    }
+
+    /// Draw pendulum B's trace over a plot of pendulum A.
+    fn overlay_compare(
+        &self,
+        painter: &Painter,
+        rect: Rect,
+        axes: Option<SeriesAxes>,
+        extract: impl Fn(&(f32, f32, f32)) -> f32,
+    ) {
+        if let (Some(axes), Some(_)) = (axes, &self.compare) {
+            let palette = self.palette();
+            draw_series_overlay(painter, rect, palette, axes, &self.compare_history, extract, palette.compare);
+        }
+    }
+
+    /// Options for a time plot titled `title` in `color` over the current
+    /// window, unwrapped, auto-scaled and without a legend.
+    fn series_options<'a>(&self, title: &'a str, color: egui::Color32, hover: Option<egui::Pos2>) -> SeriesOptions<'a> {
+        SeriesOptions {
+            view: self.time_view(),
+            time_axis: self.time_axis,
+            decimate: self.decimate_plots,
+            wraps: false,
+            fixed_range: None,
+            title,
+            color,
+            legend: &[],
+            hover,
+        }
+    }
+
+    /// Legend naming pendulum A's trace `color` and B's, or none without B.
+    fn compare_legend(&self, color: egui::Color32) -> Vec<(&'static str, egui::Color32)> {
+        match self.compare {
+            Some(_) => vec![("A", color), ("B", self.palette().compare)],
+            None => Vec::new(),
+        }
+    }
+
+    /// Padded y-range covering both A's and B's traces within the plot window.
+    fn combined_range(
+        &self,
+        extract: impl Fn(&(f32, f32, f32)) -> f32,
+        extract_compare: impl Fn(&(f32, f32, f32)) -> f32,
+    ) -> Option<(f32, f32)> {
+        self.combined_range_of(&self.history, &self.compare_history, extract, extract_compare)
+    }
+
+    /// [`Self::combined_range`] over A's series `a` and B's series `b`.
+    fn combined_range_of(
+        &self,
+        a: &VecDeque<(f32, f32, f32)>,
+        b: &VecDeque<(f32, f32, f32)>,
+        extract: impl Fn(&(f32, f32, f32)) -> f32,
+        extract_compare: impl Fn(&(f32, f32, f32)) -> f32,
+    ) -> Option<(f32, f32)> {
+        let (min_t, max_t) = self.time_view().window(self.history.back()?.0);
+        let visible = |e: &&(f32, f32, f32)| e.0 >= min_t && e.0 <= max_t;
+        let (lo, hi) = a
+            .iter()
+            .filter(visible)
+            .map(&extract)
+            .chain(b.iter().filter(visible).map(&extract_compare))
+            .filter(|v| v.is_finite())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        if lo > hi {
+            return None;
+        }
+        let span = (hi - lo).abs().max(1e-3);
+        Some((lo - 0.12 * span, hi + 0.12 * span))
+    }
+
+    /// Compact angle timeline shown under the main plot.
+    fn draw_timeline(&self, painter: &Painter, resp: &Response) {
+        let (rect, hover) = (resp.rect, resp.hover_pos());
+        let legend = self.compare_legend(self.palette().timeline);
+        let options = SeriesOptions {
+            wraps: true,
+            fixed_range: Some((-90.0, 90.0)),
+            legend: &legend,
+            ..self.series_options("Timeline", self.palette().timeline, hover)
+        };
+        let axes = draw_time_series(painter, rect, self.palette(), &self.history, |(_, th, _)| *th, options);
+        self.overlay_compare(painter, rect, axes, |(_, th, _)| *th);
+    }
+
+    /// Route edited parameters either straight into the sim or, with
+    /// `preview_params` on, into a ghost preview committed once the
+    /// controls are no longer hovered/focused.
+    fn stage_params(&mut self, staged: ParamPreview, active: bool) {
+        if self.preview_params && active {
+            self.preview = Some(staged);
+            return;
         }
+        if self.preview_params && self.preview.is_none() {
+            return;
+        }
+        self.preview = None;
+        self.sim.length = staged.length;
+        self.sim.mass = staged.mass;
+        self.sim.drag = staged.drag;
+        self.sim.gravity = staged.gravity;
     }
 
-    pub fn calculate_energy(&self) -> (f32, f32, f32) {
-        let potential = self.mass * self.gravity * self.length * (1.0 - self.theta.cos());
-        let kinetic = 0.5 * self.mass * (self.length * self.omega).powi(2);
-        (potential, kinetic, potential + kinetic)
+    /// Start capturing a PNG sequence into `capture_dir`, or stop the one running.
+    fn toggle_sequence(&mut self) {
+        if let Some(seq) = self.sequence.take() {
+            self.export_status = Some(format!("Stopped after {} frames", seq.captured));
+            return;
+        }
+        match FrameSequence::start(Path::new(&self.capture_dir), self.capture_frames) {
+            Ok(seq) => {
+                self.sequence = Some(seq);
+                self.running = true;
+                self.export_status = None;
+            }
+            Err(e) => self.export_status = Some(format!("Capture failed: {}", e)),
+        }
     }
 
     fn clamp_parameters(&mut self) {
-        self.length = self.length.clamp(0.1, 10.0);
-        self.mass = self.mass.clamp(0.1, 10.0);
-        self.drag = self.drag.clamp(0.0, 2.0);
-        self.gravity = self.gravity.clamp(0.1, 20.0);
+        self.sim.length = self.sim.length.clamp(LENGTH_RANGE.0, LENGTH_RANGE.1);
+        self.sim.mass = self.sim.mass.clamp(0.1, 10.0);
+        self.sim.drag = self.sim.drag.clamp(0.0, 2.0);
+        self.sim.gravity = self.sim.gravity.clamp(0.1, 30.0);
     }
 }
 
 impl eframe::App for PendulumApp {
-    fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, LAYOUT_KEY, &self.layout);
+    }
+
+    fn update(&mut self, ctx: &Context, frame: &mut Frame) {
         // FPS update
         let now = Instant::now();
         self.frame_count += 1;
@@ -180,6 +1586,9 @@ impl eframe::App for PendulumApp {
             self.apply_preset(idx);
         }
 
+        self.handle_shortcuts(ctx);
+        self.track_energy_reference();
+
         // timestep
         let current_time = Instant::now();
         let mut dt = (current_time - self.last_update).as_secs_f32();
@@ -187,7 +1596,15 @@ impl eframe::App for PendulumApp {
             dt = 1.0 / 60.0;
         }
         self.last_update = current_time;
+        if self.fixed_dt_enabled {
+            dt = self.fixed_dt;
+        }
         dt = dt.min(0.05) * self.simulation_speed;
+        if self.sequence.is_some() {
+            // each captured frame is exactly 1 / fps of sim time, however
+            // long the real frame took, so playback runs at true speed
+            dt = 1.0 / self.capture_fps.max(1.0);
+        }
 
         self.clamp_parameters();
 
@@ -199,10 +1616,9 @@ impl eframe::App for PendulumApp {
         }
 
         // LEFT: control panel (fixed width)
-        let side_width = 320.0;
         egui::SidePanel::left("controls_panel")
             .resizable(false)
-            .default_width(side_width)
+            .exact_width(self.layout.side_width)
             .show(ctx, |ui| {
                 ui.vertical(|ui| {
                     ui.heading("Controls");
@@ -231,17 +1647,50 @@ impl eframe::App for PendulumApp {
                                 });
                             ui.end_row();
 
+                            ui.label("Planet:");
+                            let planet = PLANET_GRAVITY
+                                .iter()
+                                .find(|(_, g)| (self.sim.gravity - g).abs() < 1e-3)
+                                .map_or("Custom", |(name, _)| *name);
+                            egui::ComboBox::from_id_source("planet_gravity")
+                                .selected_text(planet)
+                                .show_ui(ui, |ui| {
+                                    for (name, g) in PLANET_GRAVITY {
+                                        if ui.selectable_label(planet == name, format!("{name} ({g} m/s²)")).clicked() {
+                                            self.sim.gravity = g;
+                                        }
+                                    }
+                                });
+                            ui.end_row();
+
+                            let mut staged = self
+                                .preview
+                                .unwrap_or_else(|| ParamPreview::capture(self));
+                            let mut active = false;
                             ui.label("Length (m):");
-                            ui.add(egui::DragValue::new(&mut self.length).speed(0.1));
+                            active |= preview_hot(
+                                &ui.add(egui::DragValue::new(&mut staged.length).speed(0.1)),
+                            );
                             ui.end_row();
                             ui.label("Mass (kg):");
-                            ui.add(egui::DragValue::new(&mut self.mass).speed(0.1));
+                            active |= preview_hot(
+                                &ui.add(egui::DragValue::new(&mut staged.mass).speed(0.1)),
+                            );
                             ui.end_row();
                             ui.label("Drag:");
-                            ui.add(egui::DragValue::new(&mut self.drag).speed(0.01));
+                            active |= preview_hot(
+                                &ui.add(egui::DragValue::new(&mut staged.drag).speed(0.01)),
+                            );
                             ui.end_row();
                             ui.label("Gravity:");
-                            ui.add(egui::DragValue::new(&mut self.gravity).speed(0.1));
+                            active |= preview_hot(
+                                &ui.add(egui::DragValue::new(&mut staged.gravity).speed(0.1)),
+                            );
+                            ui.end_row();
+                            self.stage_params(staged, active);
+                            ui.label("Preview edits:");
+                            ui.checkbox(&mut self.preview_params, "")
+                                .on_hover_text("Hover a parameter to preview it as a ghost before committing");
                             ui.end_row();
 
                             ui.label("Init angle (°):");
@@ -250,6 +1699,152 @@ impl eframe::App for PendulumApp {
                                 self.initial_theta = deg.clamp(-179.0, 179.0).to_radians();
                             }
                             ui.end_row();
+                            ui.label("Init ω (°/s):");
+                            let mut deg_s = self.initial_omega.to_degrees();
+                            if ui
+                                .add(egui::DragValue::new(&mut deg_s).speed(2.0).clamp_range(-2000.0..=2000.0))
+                                .on_hover_text("Launch speed applied on Reset")
+                                .changed()
+                            {
+                                self.initial_omega = deg_s.to_radians();
+                            }
+                            ui.end_row();
+
+                            ui.label("Integrator:");
+                            egui::ComboBox::from_id_source("integrator")
+                                .selected_text(self.integrator.name())
+                                .show_ui(ui, |ui| {
+                                    for integrator in Integrator::ALL {
+                                        ui.selectable_value(&mut self.integrator, integrator, integrator.name());
+                                    }
+                                });
+                            ui.end_row();
+                            if self.integrator == Integrator::Rk45 {
+                                ui.label("Tolerance:");
+                                ui.add(
+                                    egui::Slider::new(&mut self.rk45_tolerance, 1e-7..=1e-2)
+                                        .logarithmic(true),
+                                );
+                                ui.end_row();
+                            }
+                            ui.label("Drag model:");
+                            egui::ComboBox::from_id_source("drag_model")
+                                .selected_text(self.sim.drag_model.name())
+                                .show_ui(ui, |ui| {
+                                    for model in DragModel::ALL {
+                                        ui.selectable_value(&mut self.sim.drag_model, model, model.name());
+                                    }
+                                })
+                                .response
+                                .on_hover_text("Linear: torque ∝ ω. Quadratic: torque ∝ ω|ω|, like air drag");
+                            ui.end_row();
+                            ui.label("Pivot friction:");
+                            ui.add(egui::DragValue::new(&mut self.sim.pivot_friction).speed(0.01).clamp_range(0.0..=5.0))
+                                .on_hover_text("Constant torque against the swing; the amplitude shrinks linearly, not exponentially");
+                            ui.end_row();
+
+                            ui.label("Elastic rod:");
+                            if ui
+                                .checkbox(&mut self.elastic, "")
+                                .on_hover_text("Spring pendulum: the rod stretches, coupling swing and bounce")
+                                .changed()
+                            {
+                                self.radius = self.sim.length;
+                                self.radius_rate = 0.0;
+                                self.energy_reference = None;
+                            }
+                            ui.end_row();
+                            if self.elastic {
+                                ui.label("Spring k:");
+                                ui.add(
+                                    egui::Slider::new(&mut self.spring_stiffness, 1.0..=500.0)
+                                        .logarithmic(true)
+                                        .suffix(" N/m"),
+                                );
+                                ui.end_row();
+                            }
+
+                            ui.label("Drive:");
+                            ui.checkbox(&mut self.drive_enabled, "")
+                                .on_hover_text("Apply a torque A·cos(ω_d·t) at the pivot");
+                            ui.end_row();
+                            if self.drive_enabled {
+                                ui.label("Drive A (N·m):");
+                                ui.add(
+                                    egui::DragValue::new(&mut self.drive_amplitude)
+                                        .speed(0.05)
+                                        .clamp_range(-DRIVE_AMPLITUDE_MAX..=DRIVE_AMPLITUDE_MAX),
+                                );
+                                ui.end_row();
+                                ui.label("Drive ω (rad/s):");
+                                let natural = (self.sim.gravity / self.sim.length.max(0.01)).sqrt();
+                                ui.add(
+                                    egui::DragValue::new(&mut self.drive_frequency)
+                                        .speed(0.01)
+                                        .clamp_range(0.0..=DRIVE_FREQUENCY_MAX),
+                                )
+                                .on_hover_text(format!("Natural frequency √(g/L) = {natural:.3} rad/s"));
+                                ui.end_row();
+                                ui.label("Bifurcation A:");
+                                ui.horizontal(|ui| {
+                                    ui.add(egui::DragValue::new(&mut self.bifurcation.from).speed(0.05));
+                                    ui.label("to");
+                                    ui.add(egui::DragValue::new(&mut self.bifurcation.to).speed(0.05));
+                                });
+                                ui.end_row();
+                                ui.label("Resolution:");
+                                ui.horizontal(|ui| {
+                                    ui.add(
+                                        egui::DragValue::new(&mut self.bifurcation.amplitudes)
+                                            .clamp_range(10..=600)
+                                            .suffix(" steps"),
+                                    )
+                                    .on_hover_text("Drive amplitudes across the range");
+                                    ui.add(
+                                        egui::DragValue::new(&mut self.bifurcation.transient)
+                                            .clamp_range(0..=2000)
+                                            .suffix(" skipped"),
+                                    )
+                                    .on_hover_text("Drive periods discarded before sampling");
+                                });
+                                ui.end_row();
+                                ui.label("");
+                                if ui
+                                    .button("Bifurcation")
+                                    .on_hover_text(
+                                        "Sweep the drive amplitude from the current state and plot θ once per \
+                                         drive period. Blocks until done; lower the resolution if slow.",
+                                    )
+                                    .clicked()
+                                {
+                                    if self.bifurcation.to < self.bifurcation.from {
+                                        std::mem::swap(&mut self.bifurcation.from, &mut self.bifurcation.to);
+                                    }
+                                    self.bifurcation_points = bifurcation(&self.sim, self.drive_frequency, &self.bifurcation);
+                                    self.selected_plot = PlotKind::Bifurcation;
+                                }
+                                ui.end_row();
+                            }
+
+                            ui.label("Escapement:");
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut self.escapement_enabled, "")
+                                    .on_hover_text("Push the bob each time it passes the bottom, like a clock");
+                                let mut deg_s = self.escapement.to_degrees();
+                                if ui
+                                    .add_enabled(
+                                        self.escapement_enabled,
+                                        egui::DragValue::new(&mut deg_s)
+                                            .speed(0.1)
+                                            .clamp_range(0.0..=90.0)
+                                            .suffix(" °/s"),
+                                    )
+                                    .changed()
+                                {
+                                    self.escapement = deg_s.to_radians();
+                                }
+                            });
+                            ui.end_row();
 
                             ui.label("Speed:");
                             ui.add(
@@ -257,47 +1852,484 @@ impl eframe::App for PendulumApp {
                                     .fixed_decimals(1),
                             );
                             ui.end_row();
+                            ui.label("Fixed dt:");
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut self.fixed_dt_enabled, "")
+                                    .on_hover_text("Same step every frame for reproducible runs; no longer real time");
+                                ui.add_enabled(
+                                    self.fixed_dt_enabled,
+                                    egui::DragValue::new(&mut self.fixed_dt)
+                                        .speed(0.0005)
+                                        .clamp_range(0.0005..=0.05)
+                                        .suffix(" s"),
+                                );
+                            });
+                            ui.end_row();
                             ui.label("Window:");
                             ui.add(egui::Slider::new(&mut self.plot_seconds, 1.0..=60.0));
                             ui.end_row();
+                            ui.label("History:");
+                            let mut capacity = self.history_capacity;
+                            if ui
+                                .add(
+                                    egui::Slider::new(&mut capacity, 256..=65536)
+                                        .logarithmic(true)
+                                        .suffix(" samples"),
+                                )
+                                .changed()
+                            {
+                                self.set_history_capacity(capacity);
+                            }
+                            ui.end_row();
+                            ui.label("Sample rate:");
+                            let mut rate = 1.0 / self.sample_dt;
+                            if ui.add(egui::Slider::new(&mut rate, 10.0..=240.0).suffix(" Hz")).changed() {
+                                self.sample_dt = 1.0 / rate;
+                                self.sample_accum = self.sample_accum.min(self.sample_dt);
+                            }
+                            ui.end_row();
                             ui.label("Auto-clear:");
                             ui.checkbox(&mut self.auto_reset_history, "");
                             ui.end_row();
                             ui.label("Timeline:");
                             ui.checkbox(&mut self.show_timeline, "");
                             ui.end_row();
+                            ui.label("Revolutions:");
+                            ui.checkbox(&mut self.show_revolutions, "")
+                                .on_hover_text("Show total turns instead of the angle");
+                            ui.end_row();
+                            ui.label("Bob size:");
+                            egui::ComboBox::from_id_source("bob_scale")
+                                .selected_text(self.bob_scale.name())
+                                .show_ui(ui, |ui| {
+                                    for scale in BobScale::ALL {
+                                        ui.selectable_value(&mut self.bob_scale, scale, scale.name());
+                                    }
+                                })
+                                .response
+                                .on_hover_text("How the drawn bob grows with mass; the period doesn't depend on it");
+                            ui.end_row();
+                            ui.label("Forces:");
+                            ui.checkbox(&mut self.show_forces, "")
+                                .on_hover_text("Gravity, tension, tangential and centripetal force at the bob");
+                            ui.end_row();
+                            ui.label("Compare B:");
+                            let mut compare = self.compare.is_some();
+                            if ui
+                                .checkbox(&mut compare, "")
+                                .on_hover_text("Run a second pendulum alongside for comparison")
+                                .changed()
+                            {
+                                self.set_compare(compare);
+                            }
+                            ui.end_row();
+                            if let Some(c) = &mut self.compare {
+                                ui.label("B length (m):");
+                                ui.add(egui::DragValue::new(&mut c.length).speed(0.1).clamp_range(0.1..=10.0));
+                                ui.end_row();
+                                ui.label("B mass (kg):");
+                                ui.add(egui::DragValue::new(&mut c.mass).speed(0.1).clamp_range(0.1..=10.0));
+                                ui.end_row();
+                                ui.label("B drag:");
+                                ui.add(egui::DragValue::new(&mut c.drag).speed(0.01).clamp_range(0.0..=2.0));
+                                ui.end_row();
+                                ui.label("B init angle (°):");
+                                let mut deg = c.initial_theta.to_degrees();
+                                if ui.add(egui::DragValue::new(&mut deg).speed(1.0)).changed() {
+                                    c.initial_theta = deg.clamp(-179.0, 179.0).to_radians();
+                                }
+                                ui.end_row();
+                            }
                         });
 
                     ui.add_space(6.0);
 
-                    ui.horizontal(|ui| {
+                    ui.horizontal_wrapped(|ui| {
                         let btn = if self.running { "⏸ Pause" } else { "▶ Start" };
-                        if ui.add_sized([88.0, 30.0], egui::Button::new(btn)).clicked() {
-                            self.running = !self.running;
-                            self.last_update = Instant::now();
-                            if self.running {
-                                let t = (Instant::now() - self.start_instant).as_secs_f32();
-                                self.push_history(t);
+                        if ui
+                            .add_sized([76.0, 30.0], egui::Button::new(btn))
+                            .on_hover_text("Space")
+                            .clicked()
+                        {
+                            self.toggle_running();
+                        }
+                        if ui
+                            .add_sized([76.0, 30.0], egui::Button::new("🔄 Reset"))
+                            .on_hover_text("R")
+                            .clicked()
+                        {
+                            self.reset();
+                        }
+                        if ui
+                            .add_sized([76.0, 30.0], egui::Button::new("⬇ Hang"))
+                            .on_hover_text("Stop at rest hanging straight down and clear the data")
+                            .clicked()
+                        {
+                            self.hang();
+                        }
+                        if ui
+                            .add_enabled(
+                                !self.running && self.replay.is_none(),
+                                egui::Button::new("⏭ Step").min_size(egui::vec2(58.0, 30.0)),
+                            )
+                            .on_hover_text("Advance one sample while paused (→)")
+                            .clicked()
+                        {
+                            self.step_once();
+                        }
+                        if ui
+                            .add_sized([84.0, 30.0], egui::Button::new("Clear Data"))
+                            .on_hover_text("C")
+                            .clicked()
+                        {
+                            self.clear_history();
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        let label = if self.settle.is_some() { "Settling…" } else { "Settle" };
+                        if ui
+                            .add_sized([88.0, 24.0], egui::Button::new(label))
+                            .on_hover_text("Damp the swing to rest at the bottom")
+                            .clicked()
+                        {
+                            self.settle(self.settle_seconds);
+                        }
+                        ui.add(
+                            egui::DragValue::new(&mut self.settle_seconds)
+                                .speed(0.1)
+                                .clamp_range(0.5..=20.0)
+                                .suffix(" s"),
+                        );
+                        let label = if self.reversal.is_some() { "Reversing…" } else { "⇄ Reverse" };
+                        if ui
+                            .add_enabled(self.replay.is_none(), egui::Button::new(label))
+                            .on_hover_text("Negate ω so the bob retraces its path back to the initial condition")
+                            .clicked()
+                        {
+                            self.reverse();
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(self.replay.is_none(), egui::Button::new("👊 Kick").min_size(egui::vec2(88.0, 24.0)))
+                            .on_hover_text("Add the impulse to ω instantly (K)")
+                            .clicked()
+                        {
+                            self.kick();
+                        }
+                        let mut deg_s = self.kick_impulse.to_degrees();
+                        if ui
+                            .add(egui::DragValue::new(&mut deg_s).speed(1.0).clamp_range(-720.0..=720.0).suffix(" °/s"))
+                            .changed()
+                        {
+                            self.kick_impulse = deg_s.to_radians();
+                        }
+                    });
+                    if let Some((d_theta, d_omega)) = self.reversal_error {
+                        // chord between the bob's start and end positions
+                        let miss = 2.0 * self.sim.length * (0.5 * d_theta).sin().abs();
+                        ui.label(format!(
+                            "Round trip: Δθ {:.4}°  Δω {:.4}°/s  ({:.2} mm)",
+                            d_theta.to_degrees(),
+                            d_omega.to_degrees(),
+                            miss * 1000.0
+                        ));
+                    }
+
+                    ui.add_space(6.0);
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut self.presets_path).desired_width(110.0));
+                        if ui
+                            .button("Save Preset")
+                            .on_hover_text("Add the current parameters as a preset and save all presets")
+                            .clicked()
+                        {
+                            let name = self.unique_preset_name("Custom");
+                            self.presets.push(self.preset_from_current(name));
+                            self.current_preset = self.presets.len() - 1;
+                            self.export_status = Some(
+                                match save_presets(&self.presets, Path::new(&self.presets_path)) {
+                                    Ok(()) => format!("Saved {} presets", self.presets.len()),
+                                    Err(e) => format!("Save failed: {}", e),
+                                },
+                            );
+                        }
+                        if ui.button("Load Presets").clicked() {
+                            match load_presets(Path::new(&self.presets_path)) {
+                                Ok(presets) => {
+                                    self.export_status = Some(format!("Loaded {} presets", presets.len()));
+                                    self.presets = presets;
+                                    self.current_preset = 0;
+                                }
+                                Err(e) => self.export_status = Some(format!("Load failed: {}", e)),
                             }
                         }
+                    });
+                    ui.horizontal(|ui| {
+                        let edit = ui.add(
+                            egui::TextEdit::singleline(&mut self.new_preset_name)
+                                .hint_text("preset name")
+                                .desired_width(150.0),
+                        );
+                        if edit.changed() {
+                            self.preset_name_error = None;
+                        }
+                        if ui.button("Add Preset").clicked() {
+                            self.add_preset_from_current();
+                        }
+                    });
+                    if let Some(err) = &self.preset_name_error {
+                        ui.colored_label(egui::Color32::from_rgb(230, 90, 90), err);
+                    }
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut self.csv_path).desired_width(180.0));
+                        if ui.button("Export CSV").clicked() {
+                            let result = write_history_csv(
+                                &self.history,
+                                &self.energy_history,
+                                Path::new(&self.csv_path),
+                            );
+                            self.export_status = Some(match result {
+                                Ok(()) => format!("Wrote {} rows", self.history.len()),
+                                Err(e) => format!("Export failed: {}", e),
+                            });
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut self.state_path).desired_width(120.0));
+                        if ui
+                            .button("Save State")
+                            .on_hover_text("Parameters, current angle and velocity, and the clock")
+                            .clicked()
+                        {
+                            self.export_status = Some(match self.run_state().save(Path::new(&self.state_path)) {
+                                Ok(()) => format!("Saved state at t = {:.2} s", self.sim_time),
+                                Err(e) => format!("Save failed: {}", e),
+                            });
+                        }
+                        if ui.button("Load State").clicked() {
+                            self.export_status = Some(match RunState::load(Path::new(&self.state_path)) {
+                                Ok(s) => {
+                                    self.apply_run_state(&s);
+                                    format!("Loaded state at t = {:.2} s", s.time)
+                                }
+                                Err(e) => format!("Load failed: {}", e),
+                            });
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut self.export_path).desired_width(180.0));
+                        if ui.button("Export .npy").clicked() {
+                            self.export_status = Some(
+                                match write_history_npy(&self.history, Path::new(&self.export_path)) {
+                                    Ok(()) => format!("Wrote {} samples", self.history.len()),
+                                    Err(e) => format!("Export failed: {}", e),
+                                },
+                            );
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        let capturing = self.sequence.is_some();
+                        ui.add_enabled_ui(!capturing, |ui| {
+                            ui.add(egui::TextEdit::singleline(&mut self.capture_dir).desired_width(90.0));
+                            ui.add(
+                                egui::DragValue::new(&mut self.capture_frames)
+                                    .clamp_range(1..=10_000)
+                                    .suffix(" fr"),
+                            );
+                            ui.add(
+                                egui::DragValue::new(&mut self.capture_fps)
+                                    .clamp_range(1.0..=240.0)
+                                    .suffix(" fps"),
+                            );
+                        });
                         if ui
-                            .add_sized([88.0, 30.0], egui::Button::new("🔄 Reset"))
+                            .button(if capturing { "⏹ Stop" } else { "Capture" })
+                            .on_hover_text("Write the pendulum view as a PNG sequence, one frame per 1 / fps of sim time")
                             .clicked()
                         {
-                            self.theta = self.initial_theta;
-                            self.omega = 0.0;
-                            if self.auto_reset_history {
-                                self.history.clear();
+                            self.toggle_sequence();
+                        }
+                        if let Some(seq) = &self.sequence {
+                            ui.small(format!("{} / {}", seq.captured, seq.total_frames));
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut self.png_path).desired_width(110.0));
+                        egui::ComboBox::from_id_source("png_target")
+                            .selected_text(self.png_target.name())
+                            .width(80.0)
+                            .show_ui(ui, |ui| {
+                                for target in [PngTarget::Pendulum, PngTarget::Plot] {
+                                    ui.selectable_value(&mut self.png_target, target, target.name());
+                                }
+                            });
+                        if ui.button("Save PNG").clicked() {
+                            self.png_pending = true;
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        let label = if self.recording { "⏹ Stop recording" } else { "⏺ Record" };
+                        if ui.button(label).on_hover_text("Store every sample for scrubbing afterwards").clicked() {
+                            self.recording = !self.recording;
+                            if self.recording {
+                                self.recorded.clear();
                             }
-                            self.start_instant = Instant::now();
                         }
                         if ui
-                            .add_sized([110.0, 30.0], egui::Button::new("Clear Data"))
+                            .add_enabled(
+                                !self.recorded.is_empty() && self.replay.is_none(),
+                                egui::Button::new("Play recording"),
+                            )
                             .clicked()
                         {
-                            self.history.clear();
+                            self.recording = false;
+                            self.running = false;
+                            self.export_status = Some(format!("Replaying {} samples", self.recorded.len()));
+                            self.replay = Some(Replay::new(self.recorded.clone()));
+                        }
+                        if self.recording {
+                            ui.colored_label(egui::Color32::from_rgb(230, 80, 80), format!("● {}", self.recorded.len()));
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut self.replay_path).desired_width(180.0));
+                        if self.replay.is_none() {
+                            if ui.button("Replay CSV").clicked() {
+                                match load_history_csv(Path::new(&self.replay_path)) {
+                                    Ok(samples) => {
+                                        self.export_status =
+                                            Some(format!("Replaying {} samples", samples.len()));
+                                        self.running = false;
+                                        self.replay = Some(Replay::new(samples));
+                                    }
+                                    Err(e) => self.export_status = Some(format!("Load failed: {}", e)),
+                                }
+                            }
+                        } else if ui.button("Exit replay").clicked() {
+                            self.replay = None;
+                        }
+                    });
+                    if let Some(replay) = &mut self.replay {
+                        ui.horizontal(|ui| {
+                            let btn = if replay.playing { "⏸" } else { "▶" };
+                            if ui.button(btn).clicked() {
+                                if !replay.playing && replay.clock >= replay.end() {
+                                    replay.clock = replay.start();
+                                }
+                                replay.playing = !replay.playing;
+                            }
+                            let range = replay.start()..=replay.end();
+                            ui.add(egui::Slider::new(&mut replay.clock, range).suffix(" s"));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Playback:");
+                            ui.add(
+                                egui::Slider::new(&mut replay.speed, 0.05..=2.0)
+                                    .logarithmic(true)
+                                    .suffix("×"),
+                            );
+                        });
+                    }
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.share_text)
+                                .hint_text("share string")
+                                .desired_width(150.0),
+                        );
+                        if ui.button("Share").on_hover_text("Copy this setup to the clipboard").clicked() {
+                            self.share_text = self.share_config().to_share_string();
+                            ui.output_mut(|o| o.copied_text = self.share_text.clone());
+                        }
+                        if ui.button("Import").clicked() {
+                            match ShareConfig::from_share_string(&self.share_text) {
+                                Ok(c) => {
+                                    self.apply_share_config(&c);
+                                    self.export_status = Some("Imported shared setup".into());
+                                }
+                                Err(e) => self.export_status = Some(e),
+                            }
+                        }
+                    });
+                    if let Some(status) = &self.export_status {
+                        ui.small(status);
+                    }
+
+                    ui.add_space(6.0);
+                    ui.horizontal(|ui| {
+                        ui.label("Target T (s):");
+                        ui.add(
+                            egui::DragValue::new(&mut self.period_target)
+                                .speed(0.01)
+                                .clamp_range(0.2..=20.0),
+                        );
+                        if ui.button("Match length").clicked() {
+                            let result = match_period_length(
+                                self.period_target,
+                                self.initial_theta,
+                                self.sim.mass,
+                                self.sim.drag_terms(),
+                                self.sim.gravity,
+                                LENGTH_RANGE,
+                            );
+                            if let Some((length, _)) = result {
+                                self.sim.length = length;
+                            }
+                            self.period_match = Some(result);
+                        }
+                        if let Some(c) = self.compare
+                            && ui
+                                .button("Match B to A")
+                                .on_hover_text("Tune B's length so its measured period equals A's")
+                                .clicked()
+                        {
+                            let result = measure_period(
+                                self.initial_theta,
+                                self.sim.length,
+                                self.sim.mass,
+                                self.sim.drag_terms(),
+                                self.sim.gravity,
+                            )
+                            .and_then(|target| {
+                                match_period_length(
+                                    target,
+                                    c.initial_theta,
+                                    c.mass,
+                                    self.sim.drag_terms_for(c.drag),
+                                    self.sim.gravity,
+                                    LENGTH_RANGE,
+                                )
+                            });
+                            if let (Some((length, _)), Some(c)) = (result, &mut self.compare) {
+                                c.length = length;
+                            }
+                            self.period_match = Some(result);
                         }
                     });
+                    if ui
+                        .button("Sweep amplitude")
+                        .on_hover_text("Measure the period from rest at 1°…170° with the current parameters")
+                        .clicked()
+                    {
+                        self.period_sweep = period_sweep(&self.sim, 1f32.to_radians(), 170f32.to_radians(), 85);
+                        self.selected_plot = PlotKind::PeriodSweep;
+                    }
+                    match self.period_match {
+                        Some(Some((length, residual))) => {
+                            ui.small(format!("L = {:.4} m, residual {:+.2e} s", length, residual));
+                            if residual.abs() >= 1e-3 && (length <= LENGTH_RANGE.0 || length >= LENGTH_RANGE.1) {
+                                ui.small(format!(
+                                    "Target out of reach: L is limited to {}–{} m",
+                                    LENGTH_RANGE.0, LENGTH_RANGE.1
+                                ));
+                            }
+                        }
+                        Some(None) => {
+                            ui.small("No period measured (over the top or overdamped)");
+                        }
+                        None => {}
+                    }
 
                     ui.add_space(8.0);
                     ui.separator();
@@ -331,10 +2363,105 @@ impl eframe::App for PendulumApp {
                         {
                             self.selected_plot = PlotKind::Phase;
                         }
+                        if ui
+                            .selectable_label(self.selected_plot == PlotKind::StepSize, "Step")
+                            .clicked()
+                        {
+                            self.selected_plot = PlotKind::StepSize;
+                        }
+                        if ui
+                            .selectable_label(self.selected_plot == PlotKind::Spectrum, "Spectrum")
+                            .clicked()
+                        {
+                            self.selected_plot = PlotKind::Spectrum;
+                        }
+                        if ui
+                            .selectable_label(self.selected_plot == PlotKind::Poincare, "Poincaré")
+                            .on_hover_text("(θ, ω) once per drive period; needs the drive enabled")
+                            .clicked()
+                        {
+                            self.selected_plot = PlotKind::Poincare;
+                        }
+                        if ui
+                            .selectable_label(self.selected_plot == PlotKind::Lissajous, "X-Y")
+                            .on_hover_text("A's angle against B's; needs Compare B")
+                            .clicked()
+                        {
+                            self.selected_plot = PlotKind::Lissajous;
+                        }
+                        if ui
+                            .selectable_label(self.selected_plot == PlotKind::PeriodSweep, "T(θ₀)")
+                            .on_hover_text("Period against release angle from the last sweep")
+                            .clicked()
+                        {
+                            self.selected_plot = PlotKind::PeriodSweep;
+                        }
+                        let mut racing = self.race.is_some();
+                        if ui
+                            .checkbox(&mut racing, "Integrators")
+                            .on_hover_text("Run RK4, symplectic and explicit Euler from the current state and plot their angles together")
+                            .changed()
+                        {
+                            self.race = racing.then(|| IntegratorRace::start(self.sim.theta, self.sim.omega));
+                            if racing {
+                                self.selected_plot = PlotKind::Integrators;
+                            } else if self.selected_plot == PlotKind::Integrators {
+                                self.selected_plot = PlotKind::Angle;
+                            }
+                        }
+                        if ui
+                            .selectable_label(self.selected_plot == PlotKind::Bifurcation, "Bifurcation")
+                            .on_hover_text("θ once per drive period against drive amplitude")
+                            .clicked()
+                        {
+                            self.selected_plot = PlotKind::Bifurcation;
+                        }
                     });
 
+                    if self.selected_plot.has_fixed_range() {
+                        let mut fit = self.auto_fit_y.contains(&self.selected_plot);
+                        if ui
+                            .checkbox(&mut fit, "Auto-fit y")
+                            .on_hover_text("Frame the visible data instead of the full physical range")
+                            .changed()
+                        {
+                            if fit {
+                                self.auto_fit_y.insert(self.selected_plot);
+                            } else {
+                                self.auto_fit_y.remove(&self.selected_plot);
+                            }
+                        }
+                    }
+                    if self.selected_plot == PlotKind::Angle {
+                        ui.checkbox(&mut self.show_small_angle, "Small-angle solution")
+                            .on_hover_text("Dashed θ₀·cos(√(g/L)·t) with the linear drag envelope, for comparison");
+                    }
+                    ui.checkbox(&mut self.decimate_plots, "Decimate dense plots")
+                        .on_hover_text("Draw only the lowest and highest sample per pixel column; spikes are kept");
+                    let mut absolute = self.time_axis == TimeAxis::Absolute;
+                    if ui.checkbox(&mut absolute, "Absolute time axis").changed() {
+                        self.time_axis = if absolute {
+                            TimeAxis::Absolute
+                        } else {
+                            TimeAxis::Relative
+                        };
+                    }
+
                     ui.add_space(8.0);
+                    ui.collapsing("Layout", |ui| {
+                        ui.add(egui::Slider::new(&mut self.layout.split_fraction, 0.3..=0.75).text("pendulum share"))
+                            .on_hover_text("Fraction of the width given to the pendulum when side by side");
+                        ui.add(egui::Slider::new(&mut self.layout.stack_threshold, 600.0..=1800.0).text("stack below (px)"))
+                            .on_hover_text("Stack the pendulum above the plot when narrower than this");
+                        ui.add(egui::Slider::new(&mut self.layout.side_width, 260.0..=480.0).text("panel width (px)"));
+                        if ui.button("Reset layout").clicked() {
+                            self.layout = LayoutPrefs::default();
+                        }
+                    });
                     ui.checkbox(&mut self.dark_mode, "Dark mode");
+                    ui.checkbox(&mut self.high_contrast, "High contrast");
+                    ui.checkbox(&mut self.reduce_motion, "Reduce motion")
+                        .on_hover_text("Animate at about 10 frames per second");
                     ui.add_space(6.0);
                     if ui.button("Help").clicked() {
                         self.show_help = !self.show_help;
@@ -343,6 +2470,29 @@ impl eframe::App for PendulumApp {
                         ui.add_space(6.0);
                         ui.label("- Left: controls fixed width");
                         ui.label("- Center/Right: adaptive content shares same top alignment and height");
+                        ui.label("- Ctrl+scroll over the pendulum: change simulation speed");
+                    }
+                    if ui.button("Debug").clicked() {
+                        self.show_debug = !self.show_debug;
+                    }
+                    if self.show_debug {
+                        ui.add_space(6.0);
+                        let (potential, kinetic, total) = self.calculate_energy();
+                        egui::Grid::new("debug_grid").num_columns(2).show(ui, |ui| {
+                            let mut row = |name: &str, value: String| {
+                                ui.label(name);
+                                ui.monospace(value);
+                                ui.end_row();
+                            };
+                            row("θ (rad)", format!("{:+.6}", self.sim.theta));
+                            row("ω (rad/s)", format!("{:+.6}", self.sim.omega));
+                            row("dt (s)", format!("{:.6}", self.last_dt));
+                            row("substeps", self.last_substeps.to_string());
+                            row("history", format!("{} / {}", self.history.len(), self.history_capacity));
+                            row("PE (J)", format!("{:.6}", potential));
+                            row("KE (J)", format!("{:.6}", kinetic));
+                            row("E (J)", format!("{:.6}", total));
+                        });
                     }
                 });
             });
@@ -354,12 +2504,33 @@ impl eframe::App for PendulumApp {
                 ui.horizontal(|ui| {
                     ui.heading("Pendulum Simulator — Adaptive Alignment");
                     ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-                        ui.label(format!(
-                            "θ:{:.1}°  ω:{:.1}°/s  FPS:{:.1}",
-                            self.theta.to_degrees(),
-                            self.omega.to_degrees(),
-                            self.current_fps
-                        ));
+                        if self.show_revolutions {
+                            let tau = std::f32::consts::TAU;
+                            ui.label(format!(
+                                "rev:{:+.2}  rate:{:+.2} rev/s  FPS:{:.1}",
+                                self.theta_unwrapped / tau,
+                                self.sim.omega / tau,
+                                self.current_fps
+                            ));
+                        } else {
+                            ui.label(format!(
+                                "θ:{:.1}°  ω:{:.1}°/s  FPS:{:.1}",
+                                self.sim.theta.to_degrees(),
+                                self.sim.omega.to_degrees(),
+                                self.current_fps
+                            ));
+                        }
+                        ui.label(format!("h:{:.2} ms", self.adaptive_dt * 1000.0));
+                        if let Some(drift) = self.energy_drift() {
+                            let color = if drift.abs() > ENERGY_DRIFT_WARN {
+                                egui::Color32::from_rgb(230, 70, 60)
+                            } else {
+                                egui::Color32::from_rgb(80, 200, 100)
+                            };
+                            ui.label(format!("ΔE:{:+.1}%", drift * 100.0))
+                                .on_hover_text("Energy drift since reset; large values mean the step is too coarse");
+                            ui.colored_label(color, "●");
+                        }
                     });
                 });
                 ui.add_space(6.0);
@@ -383,7 +2554,7 @@ impl eframe::App for PendulumApp {
 
                 // responsive stacking threshold
                 let total_width = ui.available_width();
-                let should_stack = total_width < 980.0;
+                let should_stack = total_width < self.layout.stack_threshold;
 
                 if should_stack {
                     // stacked: pendulum on top, plots below (both use half main_height)
@@ -391,68 +2562,31 @@ impl eframe::App for PendulumApp {
 
                     // pendulum block
                     let pend_size = egui::vec2(total_width, each_h);
-                    let (resp_pend, painter_pend) = ui.allocate_painter(pend_size, Sense::hover());
-                    draw_pendulum(self, &painter_pend, resp_pend.rect);
+                    let (resp_pend, painter_pend) = ui.allocate_painter(pend_size, Sense::drag());
+                    self.pendulum_canvas(ctx, &painter_pend, &resp_pend);
                     ui.add_space(6.0);
 
                     // main plot block
                     let plot_size = egui::vec2(total_width, each_h);
-                    let (resp_plot, painter_plot) = ui.allocate_painter(plot_size, Sense::hover());
-                    match self.selected_plot {
-                        PlotKind::Angle => draw_time_series(
-                            &painter_plot,
-                            resp_plot.rect,
-                            &self.history,
-                            self.plot_seconds,
-                            |(_, th, _)| *th,
-                            Some((-180.0, 180.0)),
-                            "Angle (°)",
-                            egui::Color32::from_rgb(65, 105, 225),
-                        ),
-                        PlotKind::Velocity => draw_time_series(
-                            &painter_plot,
-                            resp_plot.rect,
-                            &self.history,
-                            self.plot_seconds,
-                            |(_, _, w)| *w,
-                            None,
-                            "Angular Velocity (°/s)",
-                            egui::Color32::from_rgb(220, 20, 60),
-                        ),
-                        PlotKind::Energy => draw_time_series(
-                            &painter_plot,
-                            resp_plot.rect,
-                            &self.history,
-                            self.plot_seconds,
-                            |(_, th, _)| th.abs(),
-                            None,
-                            "Energy (proxy)",
-                            egui::Color32::from_rgb(50, 200, 100),
-                        ),
-                        PlotKind::Phase => draw_phase_plot(&painter_plot, resp_plot.rect, &self.history),
+                    let (resp_plot, painter_plot) = ui.allocate_painter(plot_size, Sense::click_and_drag());
+                    if self.selected_plot.is_time_series() {
+                        self.plot_view_input(ctx, &resp_plot);
                     }
+                    self.draw_selected_plot(&painter_plot, &resp_plot);
 
                     // timeline (optional) - responsive height
                     if self.show_timeline {
                         ui.add_space(6.0);
                         let (resp_t, painter_t) = ui.allocate_painter(
                             egui::vec2(total_width, timeline_est),
-                            Sense::hover(),
-                        );
-                        draw_time_series(
-                            &painter_t,
-                            resp_t.rect,
-                            &self.history,
-                            self.plot_seconds,
-                            |(_, th, _)| *th,
-                            Some((-90.0, 90.0)),
-                            "Timeline",
-                            egui::Color32::from_rgb(46, 139, 87),
+                            Sense::click_and_drag(),
                         );
+                        self.plot_view_input(ctx, &resp_t);
+                        self.draw_timeline(&painter_t, &resp_t);
                     }
                 } else {
                     // wide layout: left = pendulum, right = plot (same top and same height)
-                    let left_w = 0.52 * total_width; // pendulum width
+                    let left_w = self.layout.split_fraction * total_width; // pendulum width
                     let right_w = total_width - left_w - 12.0; // small spacing
 
                     // store responses so we can draw an alignment guide afterwards
@@ -462,48 +2596,19 @@ impl eframe::App for PendulumApp {
                     ui.horizontal(|ui| {
                         // pendulum area
                         let pend_size = egui::vec2(left_w, main_height);
-                        let (resp_pend, painter_pend) = ui.allocate_painter(pend_size, Sense::hover());
-                        draw_pendulum(self, &painter_pend, resp_pend.rect);
+                        let (resp_pend, painter_pend) = ui.allocate_painter(pend_size, Sense::drag());
+                        self.pendulum_canvas(ctx, &painter_pend, &resp_pend);
                         resp_pend_opt = Some(resp_pend);
 
                         ui.add_space(10.0);
 
                         // plot area
                         let plot_size = egui::vec2(right_w, main_height);
-                        let (resp_plot, painter_plot) = ui.allocate_painter(plot_size, Sense::hover());
-                        match self.selected_plot {
-                            PlotKind::Angle => draw_time_series(
-                                &painter_plot,
-                                resp_plot.rect,
-                                &self.history,
-                                self.plot_seconds,
-                                |(_, th, _)| *th,
-                                Some((-180.0, 180.0)),
-                                "Angle (°)",
-                                egui::Color32::from_rgb(65, 105, 225),
-                            ),
-                            PlotKind::Velocity => draw_time_series(
-                                &painter_plot,
-                                resp_plot.rect,
-                                &self.history,
-                                self.plot_seconds,
-                                |(_, _, w)| *w,
-                                None,
-                                "Angular Velocity (°/s)",
-                                egui::Color32::from_rgb(220, 20, 60),
-                            ),
-                            PlotKind::Energy => draw_time_series(
-                                &painter_plot,
-                                resp_plot.rect,
-                                &self.history,
-                                self.plot_seconds,
-                                |(_, th, _)| th.abs(),
-                                None,
-                                "Energy (proxy)",
-                                egui::Color32::from_rgb(50, 200, 100),
-                            ),
-                            PlotKind::Phase => draw_phase_plot(&painter_plot, resp_plot.rect, &self.history),
+                        let (resp_plot, painter_plot) = ui.allocate_painter(plot_size, Sense::click_and_drag());
+                        if self.selected_plot.is_time_series() {
+                            self.plot_view_input(ctx, &resp_plot);
                         }
+                        self.draw_selected_plot(&painter_plot, &resp_plot);
                         resp_plot_opt = Some(resp_plot);
                     });
 
@@ -545,48 +2650,107 @@ impl eframe::App for PendulumApp {
                             ui.add_space(left_w + 10.0); // shift to the right column position
                             let (resp_t, painter_t) = ui.allocate_painter(
                                 egui::vec2(timeline_width, timeline_est),
-                                Sense::hover(),
-                            );
-                            draw_time_series(
-                                &painter_t,
-                                resp_t.rect,
-                                &self.history,
-                                self.plot_seconds,
-                                |(_, th, _)| *th,
-                                Some((-90.0, 90.0)),
-                                "Timeline",
-                                egui::Color32::from_rgb(46, 139, 87),
+                                Sense::click_and_drag(),
                             );
+                            self.plot_view_input(ctx, &resp_t);
+                            self.draw_timeline(&painter_t, &resp_t);
                         });
                     }
                 } // end adaptive branch
             }); // end central vertical
         });
 
+        if let Some(replay) = &mut self.replay {
+            replay.advance(dt);
+            let (th, w) = replay.state();
+            self.sim.theta = th;
+            self.sim.omega = w;
+            ctx.request_repaint_after(self.repaint_interval());
+        }
+
+        if std::mem::take(&mut self.png_pending) {
+            let region = match self.png_target {
+                PngTarget::Pendulum => self.canvas_rect,
+                PngTarget::Plot => self.plot_rect,
+            };
+            self.png_request = Some((region, ctx.pixels_per_point()));
+            frame.request_screenshot();
+        }
+        if self.sequence.is_some() && self.running && self.replay.is_none() {
+            self.sequence_request = Some((self.canvas_rect, ctx.pixels_per_point()));
+            frame.request_screenshot();
+            ctx.request_repaint();
+        }
+
         // PHYSICS integration
-        let l = self.length.max(0.01);
-        let m = self.mass.max(1e-6);
-        let b = self.drag.max(0.0);
-        let g = self.gravity.max(0.1);
+        if self.running && self.replay.is_none() {
+            self.integrate(dt);
+            ctx.request_repaint_after(self.repaint_interval());
+        }
 
-        if self.running {
+        // ghost preview runs on its own copy of the state, so it never touches the sim
+        let drive = self.drive();
+        let sim = self.sim;
+        let mut drive_time = self.drive_time;
+        if let Some(p) = &mut self.preview {
+            p.length = p.length.clamp(0.1, 10.0);
+            p.mass = p.mass.clamp(0.1, 10.0);
+            p.drag = p.drag.clamp(0.0, 2.0);
+            p.gravity = p.gravity.clamp(0.1, 30.0);
             let mut remaining = dt;
-            let max_sub = 0.005_f32;
             while remaining > 0.0 {
-                let step = remaining.min(max_sub);
-                let (th, w) = rk4_step(self.theta, self.omega, step, l, m, b, g);
-                self.theta = th;
-                self.omega = w;
+                let step = remaining.min(0.005);
+                let params = Params {
+                    l: p.length,
+                    m: p.mass,
+                    drag: sim.drag_terms_for(p.drag),
+                    g: p.gravity,
+                    drive,
+                };
+                let (th, w) = self.integrator.step(p.theta, p.omega, step, params, drive_time);
+                p.theta = th;
+                p.omega = w;
                 remaining -= step;
+                drive_time += step;
+            }
+            ctx.request_repaint_after(self.repaint_interval());
+        }
+    }
 
-                self.sample_accum += step;
-                if self.sample_accum >= self.sample_dt {
-                    let t = (Instant::now() - self.start_instant).as_secs_f32();
-                    self.push_history(t);
-                    self.sample_accum -= self.sample_dt;
+    fn post_rendering(&mut self, _window_size_px: [u32; 2], frame: &Frame) {
+        if self.png_request.is_none() && self.sequence_request.is_none() {
+            return;
+        }
+        // one screenshot serves both requests
+        let shot = frame.screenshot();
+        if let Some((region, pixels_per_point)) = self.png_request.take() {
+            self.export_status = Some(match &shot {
+                Some(shot) => {
+                    match save_screenshot_region(shot, region, pixels_per_point, Path::new(&self.png_path)) {
+                        Ok(()) => format!("Saved {}", self.png_path),
+                        Err(e) => format!("PNG export failed: {}", e),
+                    }
                 }
+                None => "Screenshots are not supported by this renderer".into(),
+            });
+        }
+        let (Some((region, pixels_per_point)), Some(seq)) = (self.sequence_request.take(), &mut self.sequence) else {
+            return;
+        };
+        let result = match &shot {
+            Some(shot) => seq.save_frame(shot, region, pixels_per_point).map_err(|e| e.to_string()),
+            None => Err("screenshots are not supported by this renderer".into()),
+        };
+        match result {
+            Err(e) => {
+                self.export_status = Some(format!("Capture failed: {}", e));
+                self.sequence = None;
+            }
+            Ok(()) if seq.is_done() => {
+                self.export_status = Some(format!("Wrote {} frames to {}", seq.captured, seq.dir.display()));
+                self.sequence = None;
             }
-            ctx.request_repaint_after(Duration::from_millis(16));
+            Ok(()) => {}
         }
     }
 }
\ No newline at end of file
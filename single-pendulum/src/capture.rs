@@ -0,0 +1,19 @@
+// src/capture.rs
+
+pub use pendulum_core::capture::{save_screenshot_region, FrameSequence};
+
+/// Which part of the window "Save PNG" writes out.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum PngTarget {
+    Pendulum,
+    Plot,
+}
+
+impl PngTarget {
+    pub fn name(self) -> &'static str {
+        match self {
+            PngTarget::Pendulum => "Pendulum",
+            PngTarget::Plot => "Plot",
+        }
+    }
+}
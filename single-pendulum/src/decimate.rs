@@ -0,0 +1,84 @@
+// src/decimate.rs
+//
+// Min/max downsampling of time series for drawing: at most two points per
+// pixel column, so spikes survive while dense histories stay cheap to draw.
+
+/// Reduce time-sorted `(t, v)` samples to the lowest and highest sample of
+/// each of `columns` equal time buckets over `[t0, t0 + span]`, kept in time
+/// order. Every bucket's extremes survive, so the drawn envelope matches the
+/// full data; a bucket with a single sample keeps just that one.
+pub fn min_max(pts: &[(f32, f32)], t0: f32, span: f32, columns: usize) -> Vec<(f32, f32)> {
+    let columns = columns.max(1);
+    let scale = columns as f32 / span.max(1e-6);
+    let bucket_of = |t: f32| (((t - t0) * scale).floor().max(0.0) as usize).min(columns - 1);
+    let mut out = Vec::with_capacity((2 * columns).min(pts.len()));
+    // (bucket, index of its lowest sample, index of its highest sample)
+    let mut current: Option<(usize, usize, usize)> = None;
+    let flush = |out: &mut Vec<(f32, f32)>, (_, lo, hi): (usize, usize, usize)| {
+        out.push(pts[lo.min(hi)]);
+        if lo != hi {
+            out.push(pts[lo.max(hi)]);
+        }
+    };
+    for (i, &(t, v)) in pts.iter().enumerate() {
+        let bucket = bucket_of(t);
+        current = match current {
+            Some((b, lo, hi)) if b == bucket => Some((
+                b,
+                if v < pts[lo].1 { i } else { lo },
+                if v > pts[hi].1 { i } else { hi },
+            )),
+            Some(done) => {
+                flush(&mut out, done);
+                Some((bucket, i, i))
+            }
+            None => Some((bucket, i, i)),
+        };
+    }
+    if let Some(done) = current {
+        flush(&mut out, done);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Noisy chirp with a few isolated spikes, the case naive striding misses.
+    fn dense_series(n: usize) -> Vec<(f32, f32)> {
+        (0..n)
+            .map(|i| {
+                let t = i as f32 * 0.001;
+                let noise = ((i * 7919) % 101) as f32 / 101.0 - 0.5;
+                let spike = if i % 1733 == 0 { 5.0 } else { 0.0 };
+                (t, (t * t * 3.0).sin() + 0.1 * noise + spike)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keeps_min_and_max_of_every_column() {
+        let pts = dense_series(20_000);
+        let (t0, span, columns) = (0.0, 20.0, 300);
+        let out = min_max(&pts, t0, span, columns);
+        assert!(out.len() <= 2 * columns);
+        assert!(out.windows(2).all(|w| w[0].0 <= w[1].0), "output must stay in time order");
+
+        let bucket = |t: f32| (((t - t0) / span * columns as f32).floor() as usize).min(columns - 1);
+        for c in 0..columns {
+            let values = |s: &[(f32, f32)]| -> Vec<f32> { s.iter().filter(|p| bucket(p.0) == c).map(|p| p.1).collect() };
+            let (full, kept) = (values(&pts), values(&out));
+            let lo = |v: &[f32]| v.iter().copied().fold(f32::INFINITY, f32::min);
+            let hi = |v: &[f32]| v.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            assert_eq!(lo(&full), lo(&kept), "column {c} lost its minimum");
+            assert_eq!(hi(&full), hi(&kept), "column {c} lost its maximum");
+        }
+    }
+
+    #[test]
+    fn sparse_data_passes_through() {
+        let pts = dense_series(50);
+        assert_eq!(min_max(&pts, 0.0, 0.05, 1000), pts);
+    }
+}
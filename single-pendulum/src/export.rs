@@ -0,0 +1,70 @@
+// src/export.rs
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use pendulum_core::npy::write_npy_f32;
+
+/// Export the (time, theta_deg, omega_deg) history as an `N x 3` array.
+pub fn write_history_npy(history: &VecDeque<(f32, f32, f32)>, path: &Path) -> io::Result<()> {
+    let w = BufWriter::new(File::create(path)?);
+    write_npy_f32(
+        w,
+        history.len(),
+        3,
+        history.iter().flat_map(|&(t, th, w)| [t, th, w]),
+    )
+}
+
+/// Stream the history to CSV with columns time, theta_deg, omega_deg and
+/// total_energy (J). `energy` holds the (time, potential, kinetic) sampled
+/// alongside each history row. Rows that would step back in time (history
+/// kept across a reset) are skipped so the time column stays monotonic. An
+/// empty history yields just the header.
+pub fn write_history_csv(
+    history: &VecDeque<(f32, f32, f32)>,
+    energy: &VecDeque<(f32, f32, f32)>,
+    path: &Path,
+) -> io::Result<()> {
+    let mut w = BufWriter::new(File::create(path)?);
+    writeln!(w, "time,theta_deg,omega_deg,total_energy")?;
+    let mut last_t = f32::NEG_INFINITY;
+    for (&(t, th, om), &(_, p, k)) in history.iter().zip(energy) {
+        if t <= last_t {
+            continue;
+        }
+        last_t = t;
+        writeln!(w, "{},{},{},{}", t, th, om, p + k)?;
+    }
+    w.flush()
+}
+
+/// Load a history CSV (time, theta_deg, omega_deg[, ...]) as written by the
+/// exporters. Header, blank and malformed rows are skipped rather than
+/// failing the whole load; rows must keep increasing in time to be kept.
+pub fn load_history_csv(path: &Path) -> io::Result<VecDeque<(f32, f32, f32)>> {
+    let text = std::fs::read_to_string(path)?;
+    let mut history = VecDeque::new();
+    for line in text.lines() {
+        let mut cols = line.split(',').map(|c| c.trim().parse::<f32>());
+        let (Some(Ok(t)), Some(Ok(th)), Some(Ok(w))) = (cols.next(), cols.next(), cols.next()) else {
+            continue;
+        };
+        if !(t.is_finite() && th.is_finite() && w.is_finite()) {
+            continue;
+        }
+        if history.back().is_some_and(|&(last, _, _)| t <= last) {
+            continue;
+        }
+        history.push_back((t, th, w));
+    }
+    if history.len() < 2 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "need at least two valid rows",
+        ));
+    }
+    Ok(history)
+}
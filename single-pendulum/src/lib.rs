@@ -0,0 +1,13 @@
+// src/lib.rs
+//
+// GUI-free part of the single pendulum: the integrator and history export.
+// Usable as a dependency with `default-features = false`.
+
+pub mod decimate;
+pub mod export;
+pub mod physics;
+pub mod replay;
+pub mod share;
+pub mod simulation;
+pub mod spectrum;
+pub mod sweep;
@@ -2,14 +2,93 @@
 
 use eframe::egui;
 
+use single_pendulum::simulation::Simulation;
+use single_pendulum::sweep::{bifurcation, period_sweep, BifurcationSettings};
+
 use crate::app::PendulumApp;
 
 mod app;
-mod physics;
+mod capture;
+mod palette;
 mod plots;
 mod ui;
 
+/// Run the default pendulum for `seconds` in steps of `dt` without a window
+/// and print the final state: `--headless --seconds N --dt S`. Nothing
+/// depends on wall-clock time, so the output is reproducible.
+fn run_headless(seconds: f32, dt: f32) {
+    let mut sim = Simulation::default();
+    sim.run(seconds, dt);
+    println!("theta = {:.6} rad", sim.theta);
+    println!("omega = {:.6} rad/s", sim.omega);
+    println!("energy = {:.6} J", sim.total_energy());
+}
+
+/// Print the measured period of the default pendulum released from rest at
+/// 1°…170° as CSV: `--sweep`.
+fn run_sweep() {
+    println!("amplitude_deg,period_s");
+    for (amplitude, period) in period_sweep(&Simulation::default(), 1f32.to_radians(), 170f32.to_radians(), 85) {
+        println!("{:.3},{:.6}", amplitude.to_degrees(), period);
+    }
+}
+
+/// Print the bifurcation diagram of the textbook chaotic pendulum (damping
+/// ω₀/2, drive at ⅔ω₀, drive strength 0.9…1.5 × m·L²·ω₀²) as CSV:
+/// `--bifurcation --amplitudes N --transient P`.
+fn run_bifurcation(amplitudes: usize, transient: usize) {
+    let mut sim = Simulation { theta: 0.2, ..Simulation::default() };
+    let natural = (sim.gravity / sim.length).sqrt();
+    sim.drag = 0.5 * natural * sim.mass;
+    let scale = sim.mass * sim.length * sim.length * natural * natural;
+    let settings = BifurcationSettings {
+        from: 0.9 * scale,
+        to: 1.5 * scale,
+        amplitudes,
+        transient,
+        ..BifurcationSettings::default()
+    };
+    println!("drive_nm,theta_rad");
+    for (amplitude, theta) in bifurcation(&sim, natural * 2.0 / 3.0, &settings) {
+        println!("{amplitude:.4},{theta:.6}");
+    }
+}
+
 fn main() -> eframe::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let flag = |name: &str, default: f32| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .map(|s| s.parse::<f32>())
+            .unwrap_or(Ok(default))
+            .unwrap_or_else(|e| {
+                eprintln!("invalid {name} value: {e}");
+                std::process::exit(2);
+            })
+    };
+    if args.iter().any(|a| a == "--sweep") {
+        run_sweep();
+        return Ok(());
+    }
+    if args.iter().any(|a| a == "--bifurcation") {
+        let defaults = BifurcationSettings::default();
+        run_bifurcation(
+            flag("--amplitudes", defaults.amplitudes as f32).max(1.0) as usize,
+            flag("--transient", defaults.transient as f32).max(0.0) as usize,
+        );
+        return Ok(());
+    }
+    if args.iter().any(|a| a == "--headless") {
+        let (seconds, dt) = (flag("--seconds", 10.0), flag("--dt", 0.001));
+        if dt <= 0.0 {
+            eprintln!("--dt must be positive");
+            std::process::exit(2);
+        }
+        run_headless(seconds, dt);
+        return Ok(());
+    }
+
     let options = eframe::NativeOptions {
         initial_window_size: Some(egui::vec2(1360.0, 820.0)),
         min_window_size: Some(egui::vec2(780.0, 560.0)),
@@ -18,6 +97,6 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Pendulum — Adaptive Improved Alignment",
         options,
-        Box::new(|_cc| Box::new(PendulumApp::default())),
+        Box::new(|cc| Box::new(PendulumApp::new(cc))),
     )
 }
\ No newline at end of file
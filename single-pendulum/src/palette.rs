@@ -0,0 +1,113 @@
+// src/palette.rs
+
+use eframe::egui::Color32;
+
+/// Colours and stroke weights read by every drawing function, so a theme is
+/// defined in one place.
+pub struct Palette {
+    pub panel_bg: Color32,
+    pub panel_border: Color32,
+    pub grid: Color32,
+    pub axis: Color32,
+    pub label: Color32,
+    pub muted: Color32,
+    pub title: Color32,
+    pub angle: Color32,
+    pub velocity: Color32,
+    pub energy: Color32,
+    pub step: Color32,
+    pub spectrum: Color32,
+    pub timeline: Color32,
+    pub phase: Color32,
+    pub poincare: Color32,
+    pub lissajous: Color32,
+    pub marker: Color32,
+    /// Pendulum B in compare mode.
+    pub compare: Color32,
+    /// Rod colour at rest and at high speed; blended by angular speed.
+    pub rod_slow: Color32,
+    pub rod_fast: Color32,
+    pub bob: Color32,
+    pub bob_outline: Color32,
+    /// Free-body diagram arrows.
+    pub force_gravity: Color32,
+    pub force_tension: Color32,
+    pub force_tangential: Color32,
+    pub force_centripetal: Color32,
+    /// Multiplier applied to trace and rod stroke widths.
+    pub stroke_scale: f32,
+}
+
+pub const STANDARD: Palette = Palette {
+    panel_bg: Color32::from_gray(22),
+    panel_border: Color32::from_gray(90),
+    grid: Color32::from_gray(48),
+    axis: Color32::from_gray(85),
+    label: Color32::from_gray(170),
+    muted: Color32::from_gray(160),
+    title: Color32::WHITE,
+    angle: Color32::from_rgb(65, 105, 225),
+    velocity: Color32::from_rgb(220, 20, 60),
+    energy: Color32::from_rgb(50, 200, 100),
+    step: Color32::from_rgb(230, 160, 40),
+    spectrum: Color32::from_rgb(120, 200, 230),
+    timeline: Color32::from_rgb(46, 139, 87),
+    phase: Color32::from_rgb(200, 100, 255),
+    poincare: Color32::from_rgb(255, 170, 60),
+    lissajous: Color32::from_rgb(90, 200, 230),
+    marker: Color32::from_rgb(255, 255, 120),
+    compare: Color32::from_rgb(240, 200, 80),
+    rod_slow: Color32::from_rgb(100, 150, 255),
+    rod_fast: Color32::from_rgb(255, 0, 100),
+    bob: Color32::from_rgb(220, 70, 70),
+    bob_outline: Color32::from_rgb(180, 60, 60),
+    force_gravity: Color32::from_rgb(120, 220, 120),
+    force_tension: Color32::from_rgb(240, 200, 80),
+    force_tangential: Color32::from_rgb(90, 200, 230),
+    force_centripetal: Color32::from_rgb(230, 120, 230),
+    stroke_scale: 1.0,
+};
+
+/// Black panels with saturated, widely separated hues and heavier strokes.
+pub const HIGH_CONTRAST: Palette = Palette {
+    panel_bg: Color32::BLACK,
+    panel_border: Color32::WHITE,
+    grid: Color32::from_gray(90),
+    axis: Color32::from_gray(170),
+    label: Color32::WHITE,
+    muted: Color32::from_gray(220),
+    title: Color32::WHITE,
+    angle: Color32::from_rgb(0, 200, 255),
+    velocity: Color32::from_rgb(255, 60, 60),
+    energy: Color32::from_rgb(0, 255, 0),
+    step: Color32::from_rgb(255, 170, 0),
+    spectrum: Color32::from_rgb(0, 255, 255),
+    timeline: Color32::from_rgb(0, 255, 0),
+    phase: Color32::from_rgb(255, 0, 255),
+    poincare: Color32::from_rgb(255, 200, 0),
+    lissajous: Color32::from_rgb(0, 255, 255),
+    marker: Color32::WHITE,
+    compare: Color32::from_rgb(255, 255, 0),
+    rod_slow: Color32::WHITE,
+    rod_fast: Color32::from_rgb(255, 200, 0),
+    bob: Color32::from_rgb(255, 40, 40),
+    bob_outline: Color32::WHITE,
+    force_gravity: Color32::from_rgb(0, 255, 0),
+    force_tension: Color32::from_rgb(255, 255, 0),
+    force_tangential: Color32::from_rgb(0, 255, 255),
+    force_centripetal: Color32::from_rgb(255, 0, 255),
+    stroke_scale: 1.8,
+};
+
+impl Palette {
+    pub fn get(high_contrast: bool) -> &'static Palette {
+        if high_contrast { &HIGH_CONTRAST } else { &STANDARD }
+    }
+
+    /// Rod colour for `speed_ratio` in [0, 1].
+    pub fn rod_color(&self, speed_ratio: f32) -> Color32 {
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * speed_ratio.clamp(0.0, 1.0)) as u8;
+        let (s, f) = (self.rod_slow, self.rod_fast);
+        Color32::from_rgb(mix(s.r(), f.r()), mix(s.g(), f.g()), mix(s.b(), f.b()))
+    }
+}
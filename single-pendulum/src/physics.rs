@@ -1,16 +1,505 @@
 // src/physics.rs
 
-pub fn rk4_step(theta: f32, omega: f32, dt: f32, l: f32, m: f32, b: f32, g: f32) -> (f32, f32) {
-    let f = |th: f32, w: f32| -> (f32, f32) {
-        let dth = w;
-        let dw = -(g / l) * th.sin() - (b / m) * w;
-        (dth, dw)
-    };
-    let (k1t, k1w) = f(theta, omega);
-    let (k2t, k2w) = f(theta + 0.5 * dt * k1t, omega + 0.5 * dt * k1w);
-    let (k3t, k3w) = f(theta + 0.5 * dt * k2t, omega + 0.5 * dt * k2w);
-    let (k4t, k4w) = f(theta + dt * k3t, omega + dt * k3w);
-    let new_theta = theta + (dt / 6.0) * (k1t + 2.0 * k2t + 2.0 * k3t + k4t);
-    let new_omega = omega + (dt / 6.0) * (k1w + 2.0 * k2w + 2.0 * k3w + k4w);
+use serde::{Deserialize, Serialize};
+
+/// Sinusoidal drive torque `amplitude * cos(frequency * t)` about the pivot.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Drive {
+    /// Torque amplitude in N·m.
+    pub amplitude: f32,
+    /// Angular frequency in rad/s.
+    pub frequency: f32,
+}
+
+impl Drive {
+    pub const NONE: Drive = Drive { amplitude: 0.0, frequency: 0.0 };
+
+    /// Angular acceleration the drive gives a bob of mass `m` on a rod of length `l` at time `t`.
+    pub fn accel(self, t: f32, l: f32, m: f32) -> f32 {
+        self.amplitude * (self.frequency * t).cos() / (m * l * l)
+    }
+}
+
+/// Drag coefficients: `linear` gives a torque ∝ ω (viscous), `quadratic` one
+/// ∝ ω|ω| (air drag at higher speeds) and `coulomb` a constant-magnitude
+/// torque against the motion (dry friction at the pivot).
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Drag {
+    pub linear: f32,
+    pub quadratic: f32,
+    pub coulomb: f32,
+}
+
+/// Angular speed (rad/s) over which Coulomb friction ramps from zero to full
+/// strength. A hard sign(ω) would flip every substep near rest.
+const COULOMB_SMOOTHING: f32 = 0.01;
+
+impl Drag {
+    pub fn linear(b: f32) -> Self {
+        Self { linear: b, ..Self::default() }
+    }
+
+    /// Angular acceleration from drag on a bob of mass `m` at angular velocity `w`.
+    pub fn accel(self, m: f32, w: f32) -> f32 {
+        -(self.linear / m) * w
+            - (self.quadratic / m) * w * w.abs()
+            - (self.coulomb / m) * (w / COULOMB_SMOOTHING).tanh()
+    }
+}
+
+/// Which drag law the user's drag coefficient feeds.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DragModel {
+    #[default]
+    Linear,
+    Quadratic,
+}
+
+impl DragModel {
+    pub const ALL: [DragModel; 2] = [DragModel::Linear, DragModel::Quadratic];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DragModel::Linear => "Linear",
+            DragModel::Quadratic => "Quadratic",
+        }
+    }
+
+    /// Drag terms for coefficient `coefficient` under this model.
+    pub fn drag(self, coefficient: f32) -> Drag {
+        match self {
+            DragModel::Linear => Drag::linear(coefficient),
+            DragModel::Quadratic => Drag { quadratic: coefficient, ..Drag::default() },
+        }
+    }
+}
+
+/// Physical parameters shared by every step function.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Params {
+    /// Rod length in metres.
+    pub l: f32,
+    /// Bob mass in kg.
+    pub m: f32,
+    pub drag: Drag,
+    /// Gravitational acceleration in m/s².
+    pub g: f32,
+    pub drive: Drive,
+}
+
+impl Params {
+    /// Angular acceleration at angle `theta`, angular velocity `omega` and time `t`.
+    pub fn accel(self, theta: f32, omega: f32, t: f32) -> f32 {
+        -(self.g / self.l) * theta.sin() + self.drag.accel(self.m, omega) + self.drive.accel(t, self.l, self.m)
+    }
+}
+
+/// One explicit (forward) Euler step. It adds energy every step on an
+/// undamped pendulum, so it's only offered as a contrast to the others.
+pub fn euler_step(theta: f32, omega: f32, dt: f32, p: Params, t: f32) -> (f32, f32) {
+    (theta + dt * omega, omega + dt * p.accel(theta, omega, t))
+}
+
+/// One RK4 step of the driven, damped pendulum. Time rides along as a third
+/// state component so the drive is sampled at each stage's time.
+pub fn rk4_step(theta: f32, omega: f32, dt: f32, p: Params, t: f32) -> (f32, f32) {
+    let [new_theta, new_omega, _] =
+        pendulum_core::rk4_step([theta, omega, t], dt, |&[th, w, t]| [w, p.accel(th, w, t), 1.0]);
+    (new_theta, new_omega)
+}
+
+/// Leapfrog (kick-drift-kick) step. Symplectic at zero drag, so energy
+/// stays bounded over long runs instead of slowly drifting.
+pub fn symplectic_step(theta: f32, omega: f32, dt: f32, p: Params, t: f32) -> (f32, f32) {
+    let half_omega = omega + 0.5 * dt * p.accel(theta, omega, t);
+    let new_theta = theta + dt * half_omega;
+    let new_omega = half_omega + 0.5 * dt * p.accel(new_theta, half_omega, t + dt);
     (new_theta, new_omega)
-}
\ No newline at end of file
+}
+
+/// Dormand–Prince 5(4) step. Returns the fifth-order state and the largest
+/// component of the difference to the embedded fourth-order solution.
+pub fn rk45_step(theta: f32, omega: f32, dt: f32, p: Params, t: f32) -> (f32, f32, f32) {
+    let f = |th: f32, w: f32, t: f32| -> (f32, f32) { (w, p.accel(th, w, t)) };
+    // each stage's time offset is the sum of its coefficients
+    let stage = |a: &[f32], ks: &[(f32, f32)]| -> (f32, f32) {
+        let (mut th, mut w) = (theta, omega);
+        for (&c, &(kt, kw)) in a.iter().zip(ks) {
+            th += dt * c * kt;
+            w += dt * c * kw;
+        }
+        f(th, w, t + dt * a.iter().sum::<f32>())
+    };
+    let mut k = [(0.0, 0.0); 7];
+    k[0] = f(theta, omega, t);
+    k[1] = stage(&[1.0 / 5.0], &k[..1]);
+    k[2] = stage(&[3.0 / 40.0, 9.0 / 40.0], &k[..2]);
+    k[3] = stage(&[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0], &k[..3]);
+    k[4] = stage(
+        &[19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0],
+        &k[..4],
+    );
+    k[5] = stage(
+        &[9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0],
+        &k[..5],
+    );
+    let b5 = [35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0, 0.0];
+    let (mut new_theta, mut new_omega) = (theta, omega);
+    for (&c, &(kt, kw)) in b5.iter().zip(&k[..6]) {
+        new_theta += dt * c * kt;
+        new_omega += dt * c * kw;
+    }
+    k[6] = f(new_theta, new_omega, t + dt);
+    // difference between the fifth- and fourth-order weights
+    let e = [
+        71.0 / 57600.0,
+        0.0,
+        -71.0 / 16695.0,
+        71.0 / 1920.0,
+        -17253.0 / 339200.0,
+        22.0 / 525.0,
+        -1.0 / 40.0,
+    ];
+    let (mut err_t, mut err_w) = (0.0_f32, 0.0_f32);
+    for (&c, &(kt, kw)) in e.iter().zip(&k) {
+        err_t += dt * c * kt;
+        err_w += dt * c * kw;
+    }
+    (new_theta, new_omega, err_t.abs().max(err_w.abs()))
+}
+
+/// Smallest RK45 substep tried before a step is accepted regardless of error.
+pub const RK45_MIN_STEP: f32 = 1e-5;
+
+/// One accepted adaptive RK45 step from `(theta, omega)` at time `t`, no
+/// longer than `max_step`. Starts from the step size `guess` and shrinks it
+/// until the error estimate is within `tol`; `params(h)` gives the parameters
+/// for a trial step of `h`. A `max_step` under `RK45_MIN_STEP` (the sliver
+/// left at the end of a frame) is taken as one plain step. Returns
+/// `(theta, omega, step, next_guess)`.
+pub fn rk45_adaptive_step(
+    theta: f32,
+    omega: f32,
+    t: f32,
+    guess: f32,
+    max_step: f32,
+    tol: f32,
+    params: impl Fn(f32) -> Params,
+) -> (f32, f32, f32, f32) {
+    let min_step = RK45_MIN_STEP.min(max_step);
+    let mut h = guess.clamp(min_step, max_step);
+    loop {
+        let (th, w, err) = rk45_step(theta, omega, h, params(h), t);
+        let factor = if err > 0.0 { 0.9 * (tol / err).powf(0.2) } else { 5.0 };
+        if err <= tol || h <= min_step {
+            // a step cut short by `max_step` says nothing about the size
+            // the next one can take
+            let next = (h * factor.clamp(0.2, 5.0)).max(if h < guess { guess } else { 0.0 });
+            return (th, w, h, next);
+        }
+        h = (h * factor.clamp(0.2, 1.0)).clamp(min_step, max_step);
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Integrator {
+    #[default]
+    Rk4,
+    Symplectic,
+    /// Adaptive Dormand–Prince; the app varies the substep to meet a tolerance.
+    Rk45,
+}
+
+impl Integrator {
+    pub const ALL: [Integrator; 3] = [Integrator::Rk4, Integrator::Symplectic, Integrator::Rk45];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Integrator::Rk4 => "RK4",
+            Integrator::Symplectic => "Symplectic",
+            Integrator::Rk45 => "RK45 (adaptive)",
+        }
+    }
+
+    pub fn step(self, theta: f32, omega: f32, dt: f32, p: Params, t: f32) -> (f32, f32) {
+        match self {
+            Integrator::Rk4 => rk4_step(theta, omega, dt, p, t),
+            Integrator::Symplectic => symplectic_step(theta, omega, dt, p, t),
+            Integrator::Rk45 => {
+                let (th, w, _) = rk45_step(theta, omega, dt, p, t);
+                (th, w)
+            }
+        }
+    }
+}
+
+/// Linear spring replacing the rigid rod in elastic mode.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Spring {
+    /// Spring constant in N/m.
+    pub stiffness: f32,
+    /// Unstretched length in metres.
+    pub rest_length: f32,
+}
+
+/// One RK4 step of a bob on a spring in polar coordinates `(r, θ)`:
+///   `r̈ = r θ̇² + g cos θ − (k/m)(r − r₀)`
+///   `θ̈ = −(g sin θ + 2 ṙ θ̇) / r`
+/// plus drag on both rates and the drive torque. The state is `(r, ṙ, θ, ω)`;
+/// `p.l` is unused, the spring's current length `r` takes its place.
+pub fn rk4_step_elastic(state: (f32, f32, f32, f32), dt: f32, p: Params, spring: Spring, t: f32) -> (f32, f32, f32, f32) {
+    let Params { m, drag, g, drive, .. } = p;
+    let (r, r_dot, theta, omega) = state;
+    let [r, r_dot, theta, omega, _] =
+        pendulum_core::rk4_step([r, r_dot, theta, omega, t], dt, |&[r, rd, th, w, t]| {
+            let r = r.max(0.01);
+            // pivot friction resists the swing only, not the stretch
+            let radial = Drag { coulomb: 0.0, ..drag };
+            let r_acc = r * w * w + g * th.cos() - spring.stiffness / m * (r - spring.rest_length) + radial.accel(m, rd);
+            let th_acc = -(g * th.sin() + 2.0 * rd * w) / r + drag.accel(m, w) + drive.accel(t, r, m);
+            [rd, r_acc, w, th_acc, 1.0]
+        });
+    (r, r_dot, theta, omega)
+}
+
+/// (potential, kinetic) energy of the elastic pendulum, with zero potential
+/// for the bob hanging at rest at its equilibrium stretch.
+pub fn elastic_energy(r: f32, r_dot: f32, theta: f32, omega: f32, m: f32, g: f32, spring: Spring) -> (f32, f32) {
+    let k = spring.stiffness.max(1e-6);
+    let r_eq = spring.rest_length + m * g / k;
+    let stretch = |r: f32| 0.5 * k * (r - spring.rest_length).powi(2);
+    let potential = m * g * (r_eq - r * theta.cos()) + stretch(r) - stretch(r_eq);
+    let kinetic = 0.5 * m * (r_dot * r_dot + (r * omega).powi(2));
+    (potential, kinetic)
+}
+
+/// `theta` wrapped into [-π, π).
+pub fn wrap_angle(theta: f32) -> f32 {
+    (theta + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI
+}
+
+/// (potential, kinetic) energy in joules, with the bottom of the swing as zero potential.
+pub fn energy(theta: f32, omega: f32, l: f32, m: f32, g: f32) -> (f32, f32) {
+    let potential = m * g * l * (1.0 - theta.cos());
+    let kinetic = 0.5 * m * (l * omega).powi(2);
+    (potential, kinetic)
+}
+
+/// Linearised (`sin θ ≈ θ`) solution with linear drag `b` at time `t`,
+/// starting from `theta0` and `omega0`: `θ̈ + (b/m) θ̇ + (g/l) θ = 0`.
+/// Covers the under-, critically and over-damped cases.
+pub fn small_angle_theta(theta0: f32, omega0: f32, l: f32, m: f32, b: f32, g: f32, t: f32) -> f32 {
+    let w0 = (g / l).sqrt();
+    let gamma = b / (2.0 * m);
+    let decay = (-gamma * t).exp();
+    let disc = w0 * w0 - gamma * gamma;
+    if disc > 1e-6 {
+        let wd = disc.sqrt();
+        decay * (theta0 * (wd * t).cos() + (omega0 + gamma * theta0) / wd * (wd * t).sin())
+    } else if disc < -1e-6 {
+        let s = (-disc).sqrt();
+        decay * (theta0 * (s * t).cosh() + (omega0 + gamma * theta0) / s * (s * t).sinh())
+    } else {
+        decay * (theta0 + (omega0 + gamma * theta0) * t)
+    }
+}
+
+/// Small-angle length giving period `period` under gravity `g`.
+pub fn length_for_period(period: f32, g: f32) -> f32 {
+    g * (period / (2.0 * std::f32::consts::PI)).powi(2)
+}
+
+/// Simulate from rest at `theta0` and return the mean interval between
+/// upward zero crossings, or `None` if fewer than two are seen in `max_t`.
+pub fn measure_period(theta0: f32, l: f32, m: f32, drag: Drag, g: f32) -> Option<f32> {
+    let p = Params { l, m, drag, g, drive: Drive::NONE };
+    let dt = 0.001_f32;
+    let max_t = 60.0_f32;
+    let (mut theta, mut omega) = (theta0, 0.0_f32);
+    let mut t = 0.0_f32;
+    let mut crossings: Vec<f32> = Vec::new();
+    while t < max_t && crossings.len() < 4 {
+        let (th, w) = rk4_step(theta, omega, dt, p, t);
+        if theta < 0.0 && th >= 0.0 {
+            crossings.push(t + dt * (-theta / (th - theta)));
+        }
+        theta = th;
+        omega = w;
+        t += dt;
+    }
+    if crossings.len() < 2 {
+        return None;
+    }
+    Some((crossings[crossings.len() - 1] - crossings[0]) / (crossings.len() - 1) as f32)
+}
+
+/// Find the length within `min_length..=max_length` whose measured period at
+/// amplitude `theta0` equals `target`. Starts from the small-angle guess and
+/// refines with `T ∝ √L`. Returns `(length, residual)` with
+/// `residual = measured - target` at that length; a target out of reach ends
+/// at the nearest bound with the residual showing how far off it is.
+pub fn match_period_length(
+    target: f32,
+    theta0: f32,
+    m: f32,
+    drag: Drag,
+    g: f32,
+    (min_length, max_length): (f32, f32),
+) -> Option<(f32, f32)> {
+    let mut length = length_for_period(target, g).clamp(min_length, max_length);
+    for _ in 0..12 {
+        let measured = measure_period(theta0, length, m, drag, g)?;
+        let residual = measured - target;
+        let next = (length * (target / measured).powi(2)).clamp(min_length, max_length);
+        if residual.abs() < 1e-4 || next == length {
+            return Some((length, residual));
+        }
+        length = next;
+    }
+    let residual = measure_period(theta0, length, m, drag, g)? - target;
+    Some((length, residual))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The app's fixed substep.
+    const PRODUCTION_DT: f32 = 0.005;
+
+    /// Reference solution: the same equation integrated with RK4 in f64 at a
+    /// step 50× smaller, so neither truncation nor f32 rounding shows up at
+    /// the tolerances below. Returns θ at each whole multiple of `every`.
+    fn reference(theta0: f32, l: f32, m: f32, b: f32, g: f32, every: f64, samples: usize) -> Vec<f64> {
+        let (l, m, b, g) = (l as f64, m as f64, b as f64, g as f64);
+        let f = |th: f64, w: f64| (w, -(g / l) * th.sin() - (b / m) * w);
+        let dt = PRODUCTION_DT as f64 / 50.0;
+        let steps_per_sample = (every / dt).round() as usize;
+        let (mut th, mut w) = (theta0 as f64, 0.0f64);
+        let mut out = Vec::with_capacity(samples);
+        for _ in 0..samples {
+            for _ in 0..steps_per_sample {
+                let k1 = f(th, w);
+                let k2 = f(th + 0.5 * dt * k1.0, w + 0.5 * dt * k1.1);
+                let k3 = f(th + 0.5 * dt * k2.0, w + 0.5 * dt * k2.1);
+                let k4 = f(th + dt * k3.0, w + dt * k3.1);
+                th += dt / 6.0 * (k1.0 + 2.0 * k2.0 + 2.0 * k3.0 + k4.0);
+                w += dt / 6.0 * (k1.1 + 2.0 * k2.1 + 2.0 * k3.1 + k4.1);
+            }
+            out.push(th);
+        }
+        out
+    }
+
+    /// Largest |θ| difference between production-step `rk4_step` and the
+    /// reference, checked once per small-angle period over `periods` periods.
+    fn max_error(theta0: f32, b: f32, periods: usize) -> f32 {
+        let (l, m, g) = (1.0f32, 1.0f32, 9.81f32);
+        // a whole number of production steps per check
+        let steps = (std::f32::consts::TAU * (l / g).sqrt() / PRODUCTION_DT).round() as usize;
+        let every = steps as f64 * PRODUCTION_DT as f64;
+        let expected = reference(theta0, l, m, b, g, every, periods);
+        let (mut th, mut w, mut t) = (theta0, 0.0f32, 0.0f32);
+        let mut worst = 0.0f32;
+        for want in expected {
+            for _ in 0..steps {
+                (th, w) = rk4_step(th, w, PRODUCTION_DT, Params { l, m, drag: Drag::linear(b), g, drive: Drive::NONE }, t);
+                t += PRODUCTION_DT;
+            }
+            worst = worst.max((th as f64 - want).abs() as f32);
+        }
+        worst
+    }
+
+    #[test]
+    fn undamped_small_angle_matches_reference() {
+        let err = max_error(0.2, 0.0, 8);
+        assert!(err < 1e-5, "error {err} rad");
+    }
+
+    #[test]
+    fn damped_matches_reference() {
+        let err = max_error(1.0, 0.4, 8);
+        assert!(err < 1e-5, "error {err} rad");
+    }
+
+    #[test]
+    fn large_angle_matches_reference() {
+        // 170°, where sin θ is far from θ and the motion is sensitive to
+        // timing, so the step error shows most
+        let err = max_error(170f32.to_radians(), 0.0, 8);
+        assert!(err < 1e-2, "error {err} rad");
+    }
+
+    #[test]
+    fn symplectic_energy_stays_bounded_while_rk4_drifts() {
+        // a coarse 0.1 s step over about 2000 periods: leapfrog's energy
+        // error oscillates within a few percent, RK4's keeps accumulating
+        let p = Params { l: 1.0, m: 1.0, drag: Drag::default(), g: 9.81, drive: Drive::NONE };
+        let (dt, steps) = (0.1f32, 40_000);
+        let total = |th: f32, w: f32| {
+            let (potential, kinetic) = energy(th, w, p.l, p.m, p.g);
+            potential + kinetic
+        };
+        let start = total(1.0, 0.0);
+        let run = |step: fn(f32, f32, f32, Params, f32) -> (f32, f32)| {
+            let (mut th, mut w) = (1.0f32, 0.0f32);
+            let mut worst = 0.0f32;
+            for i in 0..steps {
+                (th, w) = step(th, w, dt, p, i as f32 * dt);
+                worst = worst.max((total(th, w) - start).abs() / start);
+            }
+            (worst, (total(th, w) - start).abs() / start)
+        };
+        let (symplectic_worst, _) = run(symplectic_step);
+        let (_, rk4_final) = run(rk4_step);
+        assert!(symplectic_worst < 0.05, "symplectic energy error {symplectic_worst}");
+        assert!(rk4_final > 5.0 * symplectic_worst, "RK4 drift {rk4_final} vs symplectic {symplectic_worst}");
+    }
+
+    #[test]
+    fn rk45_takes_a_leftover_below_the_minimum_step() {
+        let p = Params { l: 1.0, m: 1.0, drag: Drag::default(), g: 9.81, drive: Drive::NONE };
+        // a frame whose first accepted step stops just short of its end
+        let frame = 0.02f32;
+        let (mut th, mut w, mut t) = (0.5f32, 0.0f32, 0.0f32);
+        let mut guess = frame - 3e-6;
+        let mut remaining = frame;
+        let mut steps = 0;
+        while remaining > 0.0 {
+            let step;
+            (th, w, step, guess) = rk45_adaptive_step(th, w, t, guess, remaining, 1.0, |_| p);
+            assert!(step > 0.0 && step <= remaining, "step {step} with {remaining} left");
+            remaining -= step;
+            t += step;
+            steps += 1;
+        }
+        assert_eq!(steps, 2);
+        assert!(remaining.abs() < 1e-9);
+        assert!(guess >= frame - 3e-6, "next guess shrank to {guess}");
+        assert!(th.is_finite() && w.is_finite());
+    }
+
+    #[test]
+    fn wrapped_rotation_stays_continuous_mod_two_pi() {
+        use std::f32::consts::{PI, TAU};
+        // enough speed at the bottom to go over the top several times
+        let p = Params { l: 1.0, m: 1.0, drag: Drag::default(), g: 9.81, drive: Drive::NONE };
+        let (mut free, mut w_free) = (0.0f32, 8.0f32);
+        let (mut wrapped, mut w) = (0.0f32, 8.0f32);
+        let mut unwrapped = 0.0f32;
+        for _ in 0..2000 {
+            (free, w_free) = rk4_step(free, w_free, PRODUCTION_DT, p, 0.0);
+            let (th, new_w) = rk4_step(wrapped, w, PRODUCTION_DT, p, 0.0);
+            let next = wrap_angle(th);
+            assert!((-PI..PI).contains(&next), "{next} outside [-π, π)");
+            // a step is far below π, so the wrapped difference is the true one
+            unwrapped += wrap_angle(next - wrapped);
+            (wrapped, w) = (next, new_w);
+            let off = (wrapped - free) / TAU;
+            assert!((off - off.round()).abs() < 1e-3, "θ {wrapped} vs {free} not a whole turn apart");
+        }
+        assert!(free > 2.0 * TAU, "only reached {free} rad");
+        assert!((unwrapped - free).abs() < 1e-2, "unwrapped {unwrapped} vs {free}");
+    }
+}
@@ -2,30 +2,158 @@
 
 use std::collections::VecDeque;
 
-use eframe::egui::{Align2, Color32, FontId, Painter, Pos2, Rect, Stroke};
+use eframe::egui::{Align2, Color32, FontId, Painter, Pos2, Rect, Shape, Stroke};
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+use single_pendulum::decimate::min_max;
+use crate::palette::Palette;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum PlotKind {
     Angle,
     Velocity,
     Energy,
     Phase,
+    StepSize,
+    Spectrum,
+    Poincare,
+    Lissajous,
+    /// Measured period against release angle, from a batch sweep.
+    PeriodSweep,
+    /// Steady-state θ once per drive period against drive amplitude.
+    Bifurcation,
+    /// Angle under RK4, symplectic Euler and explicit Euler side by side.
+    Integrators,
+}
+
+impl PlotKind {
+    /// Plots drawn against time, which share the pan/zoom `TimeView`.
+    pub fn is_time_series(self) -> bool {
+        matches!(self, PlotKind::Angle | PlotKind::Velocity | PlotKind::Energy | PlotKind::StepSize)
+    }
+
+    /// Plots drawn over a fixed physical y-range unless fitted to the data.
+    pub fn has_fixed_range(self) -> bool {
+        matches!(self, PlotKind::Angle | PlotKind::Energy)
+    }
+}
+
+/// How the x-axis of a time series is labelled.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum TimeAxis {
+    /// Ticks in seconds before the newest sample ("-2s", "0s").
+    Relative,
+    /// Numeric ticks in absolute time since the run started.
+    Absolute,
+}
+
+/// A "nice" tick spacing (1, 2 or 5 times a power of ten) giving roughly
+/// `target` ticks across `span`.
+pub fn nice_step(span: f32, target: f32) -> f32 {
+    let raw = (span / target.max(1.0)).max(1e-6);
+    let mag = 10f32.powf(raw.log10().floor());
+    let norm = raw / mag;
+    let nice = if norm < 1.5 {
+        1.0
+    } else if norm < 3.5 {
+        2.0
+    } else if norm < 7.5 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * mag
+}
+
+/// Visible time range of the time series: `span` seconds ending at `end`, in
+/// absolute sim time, so a panned or zoomed view holds still while the sim
+/// runs. With no `end` it ends at the newest sample and follows live data.
+#[derive(Copy, Clone, PartialEq)]
+pub struct TimeView {
+    pub end: Option<f32>,
+    pub span: f32,
+}
+
+impl TimeView {
+    pub fn live(span: f32) -> Self {
+        Self { end: None, span }
+    }
+
+    /// `(start, end)` of the window when the newest sample is at `newest`.
+    pub fn window(self, newest: f32) -> (f32, f32) {
+        let end = self.end.unwrap_or(newest);
+        (end - self.span, end)
+    }
+}
+
+/// Linear blend from `a` at `t = 0` to `b` at `t = 1`.
+fn mix(a: Color32, b: Color32, t: f32) -> Color32 {
+    let t = t.clamp(0.0, 1.0);
+    let lerp = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    Color32::from_rgb(lerp(a.r(), b.r()), lerp(a.g(), b.g()), lerp(a.b(), b.b()))
+}
+
+/// For an angle, a jump across more than half the plot between consecutive
+/// samples is a wrap at ±180°, not motion, so the trace is broken there.
+/// Only meaningful for angle traces; other series can jump that far for real.
+fn is_wrap(a: f32, b: f32, extent: f32) -> bool {
+    (a - b).abs() > 0.5 * extent
+}
+
+/// Axes a time series was drawn with, so further traces can be overlaid on
+/// the same scale with `draw_series_overlay`.
+#[derive(Copy, Clone)]
+pub struct SeriesAxes {
+    pub min_t: f32,
+    pub seconds_window: f32,
+    pub y_min: f32,
+    pub y_max: f32,
+    /// The series is a wrapped angle, so jumps across half the plot are wraps.
+    pub wraps: bool,
+}
+
+impl SeriesAxes {
+    fn to_screen(self, rect: Rect, t: f32, v: f32) -> Pos2 {
+        let x = rect.left() + ((t - self.min_t) / self.seconds_window).clamp(0.0, 1.0) * rect.width();
+        let y = if (self.y_max - self.y_min).abs() < 1e-6 {
+            rect.center().y
+        } else {
+            rect.bottom() - ((v - self.y_min) / (self.y_max - self.y_min)).clamp(0.0, 1.0) * rect.height()
+        };
+        Pos2::new(x, y)
+    }
+}
+
+/// Window, scale and labelling for one `draw_time_series` call.
+#[derive(Copy, Clone)]
+pub struct SeriesOptions<'a> {
+    pub view: TimeView,
+    pub time_axis: TimeAxis,
+    /// Thin long windows to a min/max pair per pixel column.
+    pub decimate: bool,
+    /// The series is a wrapped angle; see [`SeriesAxes::wraps`].
+    pub wraps: bool,
+    /// y-range to draw with instead of fitting the visible samples.
+    pub fixed_range: Option<(f32, f32)>,
+    pub title: &'a str,
+    pub color: Color32,
+    pub legend: &'a [(&'a str, Color32)],
+    pub hover: Option<Pos2>,
 }
 
 pub fn draw_time_series<TExtract>(
     painter: &Painter,
     rect: Rect,
+    palette: &Palette,
     history: &VecDeque<(f32, f32, f32)>,
-    seconds_window: f32,
     extract: TExtract,
-    fixed_range: Option<(f32, f32)>,
-    title: &str,
-    color: Color32,
-) where
+    options: SeriesOptions,
+) -> Option<SeriesAxes>
+where
     TExtract: Fn(&(f32, f32, f32)) -> f32,
 {
-    painter.rect_filled(rect, 6.0, Color32::from_gray(22));
-    painter.rect_stroke(rect, 6.0, Stroke::new(1.0, Color32::from_gray(90)));
+    let SeriesOptions { view, time_axis, decimate, wraps, fixed_range, title, color, legend, hover } = options;
+    painter.rect_filled(rect, 6.0, palette.panel_bg);
+    painter.rect_stroke(rect, 6.0, Stroke::new(1.0, palette.panel_border));
 
     if history.is_empty() {
         if !title.is_empty() {
@@ -34,20 +162,21 @@ pub fn draw_time_series<TExtract>(
                 Align2::CENTER_CENTER,
                 "no data",
                 FontId::proportional(14.0),
-                Color32::from_gray(160),
+                palette.muted,
             );
         }
-        return;
+        return None;
     }
 
-    let last_t = history.back().unwrap().0;
-    let min_t = last_t - seconds_window;
+    let newest = history.back().unwrap().0;
+    let seconds_window = view.span;
+    let (min_t, last_t) = view.window(newest);
 
     let mut pts: Vec<(f32, f32)> = Vec::with_capacity(history.len());
     let mut y_min = f32::INFINITY;
     let mut y_max = f32::NEG_INFINITY;
     for e in history.iter() {
-        if e.0 < min_t {
+        if e.0 < min_t || e.0 > last_t {
             continue;
         }
         let v = extract(e);
@@ -66,10 +195,10 @@ pub fn draw_time_series<TExtract>(
                 Align2::CENTER_CENTER,
                 "not enough samples",
                 FontId::proportional(13.0),
-                Color32::from_gray(150),
+                palette.muted,
             );
         }
-        return;
+        return None;
     }
 
     let (y_min, y_max) = if let Some(r) = fixed_range {
@@ -79,39 +208,87 @@ pub fn draw_time_series<TExtract>(
         (y_min - 0.12 * span, y_max + 0.12 * span)
     };
 
-    // slightly tighter title spacing (4 px)
-    if !title.is_empty() {
-        painter.text(
-            Pos2::new(rect.left() + 6.0, rect.top() + 6.0),
-            Align2::LEFT_TOP,
-            title,
-            FontId::proportional(13.5),
-            Color32::WHITE,
-        );
+    let axes = SeriesAxes { min_t, seconds_window, y_min, y_max, wraps };
+    let width = rect.width();
+    let x_of = |t: f32| axes.to_screen(rect, t, y_min).x;
+    let y_of = |v: f32| axes.to_screen(rect, min_t, v).y;
+
+    // gridlines: about four labelled y levels and a tick every few seconds
+    let grid = Stroke::new(1.0, palette.grid);
+    let label_color = palette.label;
+    if y_max > y_min {
+        let y_step = nice_step(y_max - y_min, 4.0);
+        let decimals = (-y_step.log10().floor()).max(0.0) as usize;
+        let first = (y_min / y_step).ceil() as i64;
+        let last = (y_max / y_step).floor() as i64;
+        for k in first..=last {
+            let v = k as f32 * y_step;
+            let y = y_of(v);
+            painter.line_segment([Pos2::new(rect.left(), y), Pos2::new(rect.right(), y)], grid);
+            // keep labels clear of the title and the time labels
+            if !title.is_empty() && y > rect.top() + 22.0 && y < rect.bottom() - 14.0 {
+                painter.text(
+                    Pos2::new(rect.left() + 4.0, y - 1.0),
+                    Align2::LEFT_BOTTOM,
+                    format!("{:.*}", decimals, v),
+                    FontId::monospace(10.0),
+                    label_color,
+                );
+            }
+        }
     }
 
-    let width = rect.width();
-    let height = rect.height();
-    let x_of = |t: f32| rect.left() + ((t - min_t) / seconds_window).clamp(0.0, 1.0) * width;
-    let y_of = |v: f32| {
-        if (y_max - y_min).abs() < 1e-6 {
-            rect.center().y
-        } else {
-            rect.bottom() - ((v - y_min) / (y_max - y_min)).clamp(0.0, 1.0) * height
+    let t_step = nice_step(seconds_window, (width / 90.0).floor());
+    let t_decimals = (-t_step.log10().floor()).max(0.0) as usize;
+    // relative ticks count back from the newest sample; absolute ones sit on round times
+    let ticks: Vec<f32> = match time_axis {
+        TimeAxis::Relative => (((newest - last_t) / t_step).ceil() as i64..)
+            .map(|k| newest - k as f32 * t_step)
+            .take_while(|t| *t >= min_t)
+            .collect(),
+        TimeAxis::Absolute => {
+            let first = (min_t.max(0.0) / t_step).ceil() as i64;
+            (first..)
+                .map(|k| k as f32 * t_step)
+                .take_while(|t| *t <= last_t)
+                .collect()
         }
     };
+    for t in ticks {
+        let x = x_of(t);
+        painter.line_segment([Pos2::new(x, rect.top()), Pos2::new(x, rect.bottom())], grid);
+        if !title.is_empty() {
+            let shown = match time_axis {
+                TimeAxis::Relative => t - newest,
+                TimeAxis::Absolute => t,
+            };
+            let align = if x > rect.right() - 24.0 { Align2::RIGHT_BOTTOM } else { Align2::CENTER_BOTTOM };
+            painter.text(
+                Pos2::new(x.min(rect.right() - 4.0), rect.bottom() - 3.0),
+                align,
+                format!("{:.*}s", t_decimals, shown),
+                FontId::monospace(10.0),
+                label_color,
+            );
+        }
+    }
 
-    let mid_y = y_of((y_min + y_max) * 0.5);
-    painter.line_segment(
-        [Pos2::new(rect.left(), mid_y), Pos2::new(rect.right(), mid_y)],
-        Stroke::new(1.0, Color32::from_gray(85)),
-    );
-
-    let stroke = Stroke::new((2.0 + width / 420.0).min(4.0), color);
+    let stroke = Stroke::new((2.0 + width / 420.0).min(4.0) * palette.stroke_scale, color);
+    // more than two samples per pixel column can't show anything extra
+    let columns = width.max(1.0) as usize;
+    let decimated;
+    let drawn: &[(f32, f32)] = if decimate && pts.len() > 2 * columns {
+        decimated = min_max(&pts, min_t, seconds_window, columns);
+        &decimated
+    } else {
+        &pts
+    };
     let mut prev: Option<Pos2> = None;
-    for (t, y) in &pts {
+    for (t, y) in drawn {
         let p = Pos2::new(x_of(*t), y_of(*y));
-        if let Some(p0) = prev {
+        if let Some(p0) = prev
+            && !(wraps && is_wrap(p0.y, p.y, rect.height()))
+        {
             painter.line_segment([p0, p], stroke);
         }
         prev = Some(p);
@@ -121,49 +298,288 @@ pub fn draw_time_series<TExtract>(
         painter.circle_filled(Pos2::new(x_of(*t_last), y_of(*y_last)), 3.0, color);
     }
 
+    draw_legend(painter, rect, palette, legend);
+
+    if let Some(pos) = hover.filter(|p| rect.contains(*p)) {
+        // nearest sample in time to the pointer's x
+        let t_hover = min_t + (pos.x - rect.left()) / width * seconds_window;
+        let i = pts.partition_point(|(t, _)| *t < t_hover);
+        let nearest = [i.saturating_sub(1), i.min(pts.len() - 1)]
+            .into_iter()
+            .min_by(|a, b| (pts[*a].0 - t_hover).abs().total_cmp(&(pts[*b].0 - t_hover).abs()))
+            .map(|i| pts[i]);
+        if let Some((t, v)) = nearest {
+            let p = Pos2::new(x_of(t), y_of(v));
+            painter.line_segment(
+                [Pos2::new(p.x, rect.top()), Pos2::new(p.x, rect.bottom())],
+                Stroke::new(1.0, palette.axis),
+            );
+            painter.circle_stroke(p, 4.5, Stroke::new(1.5, palette.title));
+            let shown_t = match time_axis {
+                TimeAxis::Relative => t - newest,
+                TimeAxis::Absolute => t,
+            };
+            let galley = painter.layout_no_wrap(
+                format!("t {:.2}s  {:.3}", shown_t, v),
+                FontId::monospace(11.0),
+                palette.title,
+            );
+            // keep the label inside the plot, flipping to the left near the right edge
+            let mut at = p + eframe::egui::vec2(8.0, -galley.size().y - 8.0);
+            if at.x + galley.size().x > rect.right() - 4.0 {
+                at.x = p.x - galley.size().x - 8.0;
+            }
+            at.y = at.y.max(rect.top() + 4.0);
+            let bg = Rect::from_min_size(at, galley.size()).expand(3.0);
+            painter.rect_filled(bg, 3.0, Color32::from_black_alpha(200));
+            painter.galley(at, galley);
+        }
+    }
+
+    // slightly tighter title spacing (4 px)
     if !title.is_empty() {
         painter.text(
-            Pos2::new(rect.left() + 6.0, rect.top() + 24.0),
+            Pos2::new(rect.left() + 6.0, rect.top() + 6.0),
             Align2::LEFT_TOP,
-            format!("{:.1}", y_max),
-            FontId::monospace(11.0),
-            Color32::from_gray(200),
+            title,
+            FontId::proportional(13.5),
+            palette.title,
+        );
+    }
+    Some(axes)
+}
+
+/// Swatch-and-label box in the top-right corner, clear of the title on the
+/// left. Nothing is drawn for an empty `entries`.
+fn draw_legend(painter: &Painter, rect: Rect, palette: &Palette, entries: &[(&str, Color32)]) {
+    if entries.is_empty() {
+        return;
+    }
+    const ROW: f32 = 15.0;
+    const SWATCH: f32 = 14.0;
+    let galleys: Vec<_> = entries
+        .iter()
+        .map(|(label, _)| painter.layout_no_wrap(label.to_string(), FontId::proportional(11.5), palette.label))
+        .collect();
+    let text_width = galleys.iter().map(|g| g.size().x).fold(0.0, f32::max);
+    let size = eframe::egui::vec2(SWATCH + 6.0 + text_width, ROW * entries.len() as f32);
+    let min = Pos2::new(rect.right() - size.x - 10.0, rect.top() + 8.0);
+    let bg = Rect::from_min_size(min, size).expand(4.0);
+    painter.rect_filled(bg, 3.0, palette.panel_bg);
+    painter.rect_stroke(bg, 3.0, Stroke::new(1.0, palette.grid));
+    for (i, (galley, (_, color))) in galleys.into_iter().zip(entries).enumerate() {
+        let y = min.y + (i as f32 + 0.5) * ROW;
+        painter.line_segment(
+            [Pos2::new(min.x, y), Pos2::new(min.x + SWATCH, y)],
+            Stroke::new(2.5 * palette.stroke_scale, *color),
+        );
+        painter.galley(Pos2::new(min.x + SWATCH + 6.0, y - galley.size().y * 0.5), galley);
+    }
+}
+
+/// Draw a further trace on axes returned by `draw_time_series`.
+pub fn draw_series_overlay<TExtract>(
+    painter: &Painter,
+    rect: Rect,
+    palette: &Palette,
+    axes: SeriesAxes,
+    history: &VecDeque<(f32, f32, f32)>,
+    extract: TExtract,
+    color: Color32,
+) where
+    TExtract: Fn(&(f32, f32, f32)) -> f32,
+{
+    let stroke = Stroke::new((1.5 + rect.width() / 600.0).min(3.0) * palette.stroke_scale, color);
+    let mut prev: Option<Pos2> = None;
+    let max_t = axes.min_t + axes.seconds_window;
+    for e in history.iter().filter(|e| e.0 >= axes.min_t && e.0 <= max_t) {
+        let v = extract(e);
+        if !v.is_finite() {
+            prev = None;
+            continue;
+        }
+        let p = axes.to_screen(rect, e.0, v);
+        if let Some(p0) = prev
+            && !(axes.wraps && is_wrap(p0.y, p.y, rect.height()))
+        {
+            painter.line_segment([p0, p], stroke);
+        }
+        prev = Some(p);
+    }
+}
+
+/// Dashed curve of `f(t)` on axes returned by `draw_time_series`, sampled
+/// every couple of pixels across the window.
+pub fn draw_function_overlay(
+    painter: &Painter,
+    rect: Rect,
+    palette: &Palette,
+    axes: SeriesAxes,
+    f: impl Fn(f32) -> f32,
+    color: Color32,
+) {
+    let samples = (rect.width() / 2.0).max(2.0) as usize;
+    let points: Vec<Pos2> = (0..=samples)
+        .map(|i| axes.min_t + axes.seconds_window * i as f32 / samples as f32)
+        .map(|t| (t, f(t)))
+        .filter(|(_, v)| v.is_finite())
+        .map(|(t, v)| axes.to_screen(rect, t, v))
+        .collect();
+    let stroke = Stroke::new(1.5 * palette.stroke_scale, color);
+    painter.extend(Shape::dashed_line(&points, stroke, 6.0, 4.0));
+}
+
+/// Labelled gridlines for a plot mapping `x_range` across and `y_range` up
+/// `rect` linearly: x ticks along the bottom, y ticks up the left side, each
+/// value followed by its entry in `units`.
+fn draw_xy_grid(
+    painter: &Painter,
+    rect: Rect,
+    palette: &Palette,
+    (x_min, x_max): (f32, f32),
+    (y_min, y_max): (f32, f32),
+    units: (&str, &str),
+) {
+    let x_of = |x: f32| rect.left() + (x - x_min) / (x_max - x_min) * rect.width();
+    let y_of = |y: f32| rect.bottom() - (y - y_min) / (y_max - y_min) * rect.height();
+    let grid = Stroke::new(1.0, palette.grid);
+    let x_step = nice_step(x_max - x_min, (rect.width() / 90.0).floor());
+    let x_decimals = (-x_step.log10().floor()).max(0.0) as usize;
+    for k in (x_min / x_step).ceil() as i64..=(x_max / x_step).floor() as i64 {
+        let v = k as f32 * x_step;
+        let x = x_of(v);
+        painter.line_segment([Pos2::new(x, rect.top()), Pos2::new(x, rect.bottom())], grid);
+        let align = if x > rect.right() - 24.0 { Align2::RIGHT_BOTTOM } else { Align2::CENTER_BOTTOM };
+        painter.text(
+            Pos2::new(x.min(rect.right() - 4.0), rect.bottom() - 3.0),
+            align,
+            format!("{:.*}{}", x_decimals, v, units.0),
+            FontId::monospace(10.0),
+            palette.label,
         );
+    }
+    let y_step = nice_step(y_max - y_min, 4.0);
+    let y_decimals = (-y_step.log10().floor()).max(0.0) as usize;
+    for k in (y_min / y_step).ceil() as i64..=(y_max / y_step).floor() as i64 {
+        let v = k as f32 * y_step;
+        let y = y_of(v);
+        painter.line_segment([Pos2::new(rect.left(), y), Pos2::new(rect.right(), y)], grid);
+        // keep labels clear of the title and the x labels
+        if y > rect.top() + 22.0 && y < rect.bottom() - 14.0 {
+            painter.text(
+                Pos2::new(rect.left() + 4.0, y - 1.0),
+                Align2::LEFT_BOTTOM,
+                format!("{:.*}{}", y_decimals, v, units.1),
+                FontId::monospace(10.0),
+                palette.label,
+            );
+        }
+    }
+}
+
+/// Period against release angle from `sweep::period_sweep`, with the
+/// small-angle period `t0` as a dashed reference line.
+pub fn draw_period_sweep(painter: &Painter, rect: Rect, palette: &Palette, sweep: &[(f32, f32)], t0: f32) {
+    painter.rect_filled(rect, 6.0, palette.panel_bg);
+    painter.rect_stroke(rect, 6.0, Stroke::new(1.0, palette.panel_border));
+    let title = |painter: &Painter| {
         painter.text(
-            Pos2::new(rect.left() + 6.0, rect.bottom() - 18.0),
+            Pos2::new(rect.left() + 6.0, rect.top() + 6.0),
             Align2::LEFT_TOP,
-            format!("{:.1}", y_min),
-            FontId::monospace(11.0),
-            Color32::from_gray(200),
+            "Period vs amplitude",
+            FontId::proportional(13.5),
+            palette.title,
+        )
+    };
+    if sweep.len() < 2 {
+        title(painter);
+        painter.text(
+            rect.center(),
+            Align2::CENTER_CENTER,
+            "no sweep — press Sweep amplitude",
+            FontId::proportional(14.0),
+            palette.muted,
         );
+        return;
+    }
+
+    let x_max = sweep.iter().map(|(a, _)| a.to_degrees()).fold(1.0, f32::max);
+    let t_max = sweep.iter().map(|(_, t)| *t).fold(t0, f32::max);
+    // start the y-axis just under T₀ so the lengthening fills the plot
+    let (y_min, y_max) = (t0 * 0.95, t_max + 0.08 * (t_max - t0 * 0.95));
+    let x_of = |a: f32| rect.left() + (a.to_degrees() / x_max).clamp(0.0, 1.0) * rect.width();
+    let y_of = |t: f32| rect.bottom() - ((t - y_min) / (y_max - y_min)).clamp(0.0, 1.0) * rect.height();
+    draw_xy_grid(painter, rect, palette, (0.0, x_max), (y_min, y_max), ("°", "s"));
+
+    let reference = [Pos2::new(rect.left(), y_of(t0)), Pos2::new(rect.right(), y_of(t0))];
+    painter.extend(Shape::dashed_line(&reference, Stroke::new(1.2, palette.axis), 6.0, 4.0));
+    let points: Vec<Pos2> = sweep.iter().map(|&(a, t)| Pos2::new(x_of(a), y_of(t))).collect();
+    painter.add(Shape::line(points.clone(), Stroke::new(1.8 * palette.stroke_scale, palette.angle)));
+    for p in points {
+        painter.circle_filled(p, 2.5, palette.marker);
+    }
+    title(painter);
+}
+
+/// Bifurcation diagram from `sweep::bifurcation`: one dot per sampled drive
+/// period, θ in degrees against drive amplitude `range` in N·m.
+pub fn draw_bifurcation(painter: &Painter, rect: Rect, palette: &Palette, points: &[(f32, f32)], range: (f32, f32)) {
+    painter.rect_filled(rect, 6.0, palette.panel_bg);
+    painter.rect_stroke(rect, 6.0, Stroke::new(1.0, palette.panel_border));
+    let title = |painter: &Painter| {
         painter.text(
-            Pos2::new(rect.right() - 60.0, rect.bottom() - 18.0),
+            Pos2::new(rect.left() + 6.0, rect.top() + 6.0),
             Align2::LEFT_TOP,
-            format!("-{:.0}s", seconds_window),
-            FontId::monospace(11.0),
-            Color32::from_gray(170),
+            format!("Bifurcation (θ per drive period, {} points)", points.len()),
+            FontId::proportional(13.5),
+            palette.title,
+        )
+    };
+    if points.is_empty() || range.1 <= range.0 {
+        title(painter);
+        painter.text(
+            rect.center(),
+            Align2::CENTER_CENTER,
+            "no diagram — enable the drive and press Bifurcation",
+            FontId::proportional(14.0),
+            palette.muted,
         );
+        return;
     }
+
+    let (a_min, a_max) = range;
+    let x_of = |a: f32| rect.left() + ((a - a_min) / (a_max - a_min)).clamp(0.0, 1.0) * rect.width();
+    let y_of = |th: f32| rect.center().y - (th.to_degrees() / 180.0).clamp(-1.0, 1.0) * rect.height() * 0.5;
+    draw_xy_grid(painter, rect, palette, range, (-180.0, 180.0), ("", "°"));
+    for &(a, th) in points {
+        if th.is_finite() {
+            painter.circle_filled(Pos2::new(x_of(a), y_of(th)), 1.0, palette.poincare);
+        }
+    }
+    title(painter);
 }
 
-pub fn draw_phase_plot(painter: &Painter, rect: Rect, history: &VecDeque<(f32, f32, f32)>) {
-    painter.rect_filled(rect, 6.0, Color32::from_gray(22));
-    painter.rect_stroke(rect, 6.0, Stroke::new(1.0, Color32::from_gray(90)));
-    painter.text(
-        Pos2::new(rect.left() + 6.0, rect.top() + 6.0),
-        Align2::LEFT_TOP,
-        "Phase (θ vs ω)",
-        FontId::proportional(13.5),
-        Color32::WHITE,
-    );
+pub fn draw_phase_plot(painter: &Painter, rect: Rect, palette: &Palette, history: &VecDeque<(f32, f32, f32)>) {
+    painter.rect_filled(rect, 6.0, palette.panel_bg);
+    painter.rect_stroke(rect, 6.0, Stroke::new(1.0, palette.panel_border));
+    let title = |painter: &Painter| {
+        painter.text(
+            Pos2::new(rect.left() + 6.0, rect.top() + 6.0),
+            Align2::LEFT_TOP,
+            "Phase (θ vs ω)",
+            FontId::proportional(13.5),
+            palette.title,
+        )
+    };
 
     if history.is_empty() {
+        title(painter);
         painter.text(
             Pos2::new(rect.center().x, rect.center().y),
             Align2::CENTER_CENTER,
             "no data",
             FontId::proportional(14.0),
-            Color32::from_gray(160),
+            palette.muted,
         );
         return;
     }
@@ -185,6 +601,7 @@ pub fn draw_phase_plot(painter: &Painter, rect: Rect, history: &VecDeque<(f32, f
         }
     }
     if pts.len() < 2 {
+        title(painter);
         return;
     }
 
@@ -202,31 +619,274 @@ pub fn draw_phase_plot(painter: &Painter, rect: Rect, history: &VecDeque<(f32, f
         rect.bottom() - ((w - w_min) / (w_max - w_min)).clamp(0.0, 1.0) * height
     };
 
+    // gridlines with θ ticks along the bottom and ω ticks up the left side
+    draw_xy_grid(painter, rect, palette, (th_min, th_max), (w_min, w_max), ("°", "°/s"));
+
     painter.line_segment(
         [
             Pos2::new(rect.left(), y_of(0.0)),
             Pos2::new(rect.right(), y_of(0.0)),
         ],
-        Stroke::new(1.0, Color32::from_gray(85)),
+        Stroke::new(1.0, palette.axis),
     );
     painter.line_segment(
         [
             Pos2::new(x_of(0.0), rect.top()),
             Pos2::new(x_of(0.0), rect.bottom()),
         ],
-        Stroke::new(1.0, Color32::from_gray(85)),
+        Stroke::new(1.0, palette.axis),
     );
 
-    let stroke = Stroke::new(1.6, Color32::from_rgb(200, 100, 255));
+    // fade the trail from the background at the oldest sample to full colour
+    // at the newest, so the direction of motion reads at a glance
+    let last = (pts.len() - 1) as f32;
     let mut prev: Option<Pos2> = None;
-    for (th, w) in pts.iter() {
+    for (i, (th, w)) in pts.iter().enumerate() {
         let p = Pos2::new(x_of(*th), y_of(*w));
+        if let Some(p0) = prev
+            && !is_wrap(p0.x, p.x, width)
+        {
+            let color = mix(palette.panel_bg, palette.phase, 0.15 + 0.85 * i as f32 / last);
+            painter.line_segment([p0, p], Stroke::new(1.6 * palette.stroke_scale, color));
+        }
+        prev = Some(p);
+    }
+    if let Some((th, w)) = pts.last() {
+        painter.circle_filled(Pos2::new(x_of(*th), y_of(*w)), 3.0, palette.marker);
+    }
+    // on top of the gridlines
+    title(painter);
+}
+/// Magnitude spectrum from `spectrum::magnitude_spectrum`. The x-axis spans a
+/// few multiples of the strongest peak so a slow swing isn't squashed against
+/// the left edge by the Nyquist range.
+pub fn draw_spectrum(
+    painter: &Painter,
+    rect: Rect,
+    palette: &Palette,
+    spectrum: &[(f32, f32)],
+    title: &str,
+    color: Color32,
+) {
+    painter.rect_filled(rect, 6.0, palette.panel_bg);
+    painter.rect_stroke(rect, 6.0, Stroke::new(1.0, palette.panel_border));
+    painter.text(
+        Pos2::new(rect.left() + 6.0, rect.top() + 6.0),
+        Align2::LEFT_TOP,
+        title,
+        FontId::proportional(13.5),
+        palette.title,
+    );
+
+    if spectrum.len() < 2 {
+        painter.text(
+            Pos2::new(rect.center().x, rect.center().y),
+            Align2::CENTER_CENTER,
+            "not enough samples",
+            FontId::proportional(13.0),
+            palette.muted,
+        );
+        return;
+    }
+
+    // skip DC: the mean is removed, so bin 0 only carries leakage
+    let (peak_f, peak_mag) = spectrum[1..]
+        .iter()
+        .copied()
+        .fold((0.0, 0.0), |best, (f, m)| if m > best.1 { (f, m) } else { best });
+    let nyquist = spectrum.last().map_or(1.0, |(f, _)| *f);
+    let f_max = (peak_f * 6.0).clamp(spectrum[1].0.max(0.5), nyquist.max(1e-3));
+    let mag_max = peak_mag.max(1e-6) * 1.15;
+
+    let x_of = |f: f32| rect.left() + (f / f_max).clamp(0.0, 1.0) * rect.width();
+    let y_of = |m: f32| rect.bottom() - (m / mag_max).clamp(0.0, 1.0) * rect.height();
+
+    let stroke = Stroke::new(1.8 * palette.stroke_scale, color);
+    let mut prev: Option<Pos2> = None;
+    for &(f, m) in spectrum.iter().take_while(|(f, _)| *f <= f_max) {
+        let p = Pos2::new(x_of(f), y_of(m));
         if let Some(p0) = prev {
             painter.line_segment([p0, p], stroke);
         }
         prev = Some(p);
     }
-    if let Some((th, w)) = pts.last() {
-        painter.circle_filled(Pos2::new(x_of(*th), y_of(*w)), 3.0, Color32::from_rgb(255, 255, 120));
+
+    if peak_mag > 0.0 {
+        let peak = Pos2::new(x_of(peak_f), y_of(peak_mag));
+        painter.circle_filled(peak, 3.0, color);
+        painter.text(
+            peak + eframe::egui::vec2(6.0, -4.0),
+            Align2::LEFT_BOTTOM,
+            format!("{:.3} Hz", peak_f),
+            FontId::monospace(11.0),
+            palette.label,
+        );
     }
-}
\ No newline at end of file
+    painter.text(
+        Pos2::new(rect.right() - 6.0, rect.bottom() - 18.0),
+        Align2::RIGHT_TOP,
+        format!("{:.2} Hz", f_max),
+        FontId::monospace(11.0),
+        palette.label,
+    );
+}
+
+/// Poincaré section: one (θ, ω) point per drive period, in radians. θ is
+/// already wrapped, so the x-axis is fixed at [-π, π].
+pub fn draw_poincare_plot(painter: &Painter, rect: Rect, palette: &Palette, points: &VecDeque<(f32, f32)>) {
+    painter.rect_filled(rect, 6.0, palette.panel_bg);
+    painter.rect_stroke(rect, 6.0, Stroke::new(1.0, palette.panel_border));
+    painter.text(
+        Pos2::new(rect.left() + 6.0, rect.top() + 6.0),
+        Align2::LEFT_TOP,
+        format!("Poincaré section ({} points)", points.len()),
+        FontId::proportional(13.5),
+        palette.title,
+    );
+
+    if points.is_empty() {
+        painter.text(
+            Pos2::new(rect.center().x, rect.center().y),
+            Align2::CENTER_CENTER,
+            "no data — enable the drive",
+            FontId::proportional(14.0),
+            palette.muted,
+        );
+        return;
+    }
+
+    let pi = std::f32::consts::PI;
+    let w_abs = points
+        .iter()
+        .map(|(_, w)| w.abs())
+        .filter(|w| w.is_finite())
+        .fold(1e-3, f32::max)
+        * 1.12;
+    let x_of = |th: f32| rect.left() + ((th + pi) / (2.0 * pi)).clamp(0.0, 1.0) * rect.width();
+    let y_of = |w: f32| rect.center().y - (w / w_abs).clamp(-1.0, 1.0) * rect.height() * 0.5;
+
+    painter.line_segment(
+        [Pos2::new(rect.left(), y_of(0.0)), Pos2::new(rect.right(), y_of(0.0))],
+        Stroke::new(1.0, palette.axis),
+    );
+    painter.line_segment(
+        [Pos2::new(x_of(0.0), rect.top()), Pos2::new(x_of(0.0), rect.bottom())],
+        Stroke::new(1.0, palette.axis),
+    );
+
+    let dot = palette.poincare;
+    for &(th, w) in points.iter() {
+        if th.is_finite() && w.is_finite() {
+            painter.circle_filled(Pos2::new(x_of(th), y_of(w)), 1.3, dot);
+        }
+    }
+
+    painter.text(
+        Pos2::new(rect.left() + 6.0, rect.bottom() - 18.0),
+        Align2::LEFT_TOP,
+        "-π",
+        FontId::monospace(11.0),
+        palette.label,
+    );
+    painter.text(
+        Pos2::new(rect.right() - 6.0, rect.bottom() - 18.0),
+        Align2::RIGHT_TOP,
+        "π",
+        FontId::monospace(11.0),
+        palette.label,
+    );
+    painter.text(
+        Pos2::new(rect.left() + 6.0, rect.top() + 24.0),
+        Align2::LEFT_TOP,
+        format!("ω {:.1} rad/s", w_abs),
+        FontId::monospace(11.0),
+        palette.label,
+    );
+}
+
+/// Pendulum A's angle against pendulum B's, pairing samples with equal
+/// timestamps. Closed figures appear when the two frequencies are in a
+/// simple ratio.
+pub fn draw_lissajous_plot(
+    painter: &Painter,
+    rect: Rect,
+    palette: &Palette,
+    a: &VecDeque<(f32, f32, f32)>,
+    b: &VecDeque<(f32, f32, f32)>,
+) {
+    painter.rect_filled(rect, 6.0, palette.panel_bg);
+    painter.rect_stroke(rect, 6.0, Stroke::new(1.0, palette.panel_border));
+    painter.text(
+        Pos2::new(rect.left() + 6.0, rect.top() + 6.0),
+        Align2::LEFT_TOP,
+        "Lissajous (θ A vs θ B)",
+        FontId::proportional(13.5),
+        palette.title,
+    );
+
+    // both histories are sorted by time, so pair them with a merge walk
+    let mut pts = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let (ta, tb) = (a[i].0, b[j].0);
+        if (ta - tb).abs() < 1e-6 {
+            if a[i].1.is_finite() && b[j].1.is_finite() {
+                pts.push((a[i].1, b[j].1));
+            }
+            i += 1;
+            j += 1;
+        } else if ta < tb {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    if pts.len() < 2 {
+        painter.text(
+            rect.center(),
+            Align2::CENTER_CENTER,
+            "needs Compare B running",
+            FontId::proportional(14.0),
+            palette.muted,
+        );
+        return;
+    }
+
+    let (mut x_min, mut x_max, mut y_min, mut y_max) =
+        (f32::INFINITY, f32::NEG_INFINITY, f32::INFINITY, f32::NEG_INFINITY);
+    for &(x, y) in &pts {
+        x_min = x_min.min(x);
+        x_max = x_max.max(x);
+        y_min = y_min.min(y);
+        y_max = y_max.max(y);
+    }
+    let x_span = (x_max - x_min).max(1e-3);
+    let y_span = (y_max - y_min).max(1e-3);
+    let (x_min, x_max) = (x_min - 0.12 * x_span, x_max + 0.12 * x_span);
+    let (y_min, y_max) = (y_min - 0.12 * y_span, y_max + 0.12 * y_span);
+
+    let width = rect.width();
+    let height = rect.height();
+    let x_of = |x: f32| rect.left() + ((x - x_min) / (x_max - x_min)).clamp(0.0, 1.0) * width;
+    let y_of = |y: f32| rect.bottom() - ((y - y_min) / (y_max - y_min)).clamp(0.0, 1.0) * height;
+
+    let axis = Stroke::new(1.0, palette.axis);
+    painter.line_segment([Pos2::new(rect.left(), y_of(0.0)), Pos2::new(rect.right(), y_of(0.0))], axis);
+    painter.line_segment([Pos2::new(x_of(0.0), rect.top()), Pos2::new(x_of(0.0), rect.bottom())], axis);
+
+    let stroke = Stroke::new(1.6 * palette.stroke_scale, palette.lissajous);
+    let mut prev: Option<Pos2> = None;
+    for &(x, y) in &pts {
+        let p = Pos2::new(x_of(x), y_of(y));
+        if let Some(p0) = prev
+            && !is_wrap(p0.x, p.x, width)
+            && !is_wrap(p0.y, p.y, height)
+        {
+            painter.line_segment([p0, p], stroke);
+        }
+        prev = Some(p);
+    }
+    if let Some(p) = prev {
+        painter.circle_filled(p, 3.0, palette.marker);
+    }
+}
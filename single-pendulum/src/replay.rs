@@ -0,0 +1,58 @@
+// src/replay.rs
+
+use std::collections::VecDeque;
+
+/// Plays back a recorded (time, theta_deg, omega_deg) history by a playback
+/// clock instead of integrating.
+pub struct Replay {
+    pub samples: VecDeque<(f32, f32, f32)>,
+    pub clock: f32,
+    pub playing: bool,
+    /// Playback rate; below 1 for slow motion.
+    pub speed: f32,
+}
+
+impl Replay {
+    /// `samples` must be non-empty and sorted by time.
+    pub fn new(samples: VecDeque<(f32, f32, f32)>) -> Self {
+        let clock = samples.front().map_or(0.0, |s| s.0);
+        Self {
+            samples,
+            clock,
+            playing: true,
+            speed: 1.0,
+        }
+    }
+
+    pub fn start(&self) -> f32 {
+        self.samples.front().map_or(0.0, |s| s.0)
+    }
+
+    pub fn end(&self) -> f32 {
+        self.samples.back().map_or(0.0, |s| s.0)
+    }
+
+    /// Advance the playback clock, stopping at the last sample.
+    pub fn advance(&mut self, dt: f32) {
+        if self.playing {
+            self.clock = (self.clock + dt * self.speed).min(self.end());
+            if self.clock >= self.end() {
+                self.playing = false;
+            }
+        }
+    }
+
+    /// Linearly interpolated (theta, omega) in radians at the playback clock.
+    pub fn state(&self) -> (f32, f32) {
+        let i = self.samples.partition_point(|s| s.0 <= self.clock);
+        let (th, w) = match (i.checked_sub(1).and_then(|j| self.samples.get(j)), self.samples.get(i)) {
+            (Some(a), Some(b)) => {
+                let f = ((self.clock - a.0) / (b.0 - a.0).max(1e-9)).clamp(0.0, 1.0);
+                (a.1 + (b.1 - a.1) * f, a.2 + (b.2 - a.2) * f)
+            }
+            (Some(a), None) | (None, Some(a)) => (a.1, a.2),
+            (None, None) => (0.0, 0.0),
+        };
+        (th.to_radians(), w.to_radians())
+    }
+}
@@ -0,0 +1,182 @@
+// src/share.rs
+
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+
+use crate::physics::{DragModel, Integrator};
+
+/// Bumped whenever fields change meaning; older strings are migrated where
+/// their meaning is known and rejected otherwise, never silently misread.
+/// Version 2 added the drag model, which decides what `drag` means.
+pub const SHARE_VERSION: u32 = 2;
+
+/// Everything needed to reproduce a setup, encoded as a compact string that
+/// can be pasted into a forum post or bug report.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShareConfig {
+    pub version: u32,
+    pub length: f32,
+    pub mass: f32,
+    pub drag: f32,
+    pub gravity: f32,
+    pub initial_theta: f32,
+    /// Absent from older strings, which launched from rest.
+    #[serde(default)]
+    pub initial_omega: f32,
+    pub simulation_speed: f32,
+    // The rest are absent from older strings, which used the defaults:
+    // RK4, linear drag, no pivot friction, no drive and a rigid rod.
+    #[serde(default)]
+    pub integrator: Integrator,
+    #[serde(default)]
+    pub drag_model: DragModel,
+    #[serde(default)]
+    pub pivot_friction: f32,
+    #[serde(default)]
+    pub drive_enabled: bool,
+    /// Drive torque amplitude (N·m) and angular frequency (rad/s).
+    #[serde(default)]
+    pub drive_amplitude: f32,
+    #[serde(default)]
+    pub drive_frequency: f32,
+    #[serde(default)]
+    pub elastic: bool,
+    /// Spring constant in N/m, used in elastic mode.
+    #[serde(default)]
+    pub spring_stiffness: f32,
+}
+
+impl ShareConfig {
+    /// JSON, then URL-safe base64 without padding.
+    pub fn to_share_string(&self) -> String {
+        let json = serde_json::to_vec(self).expect("ShareConfig always serializes");
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    /// Decode a share string, migrating older versions. Fails unless length,
+    /// mass, gravity and speed are positive, drag, friction and drive
+    /// frequency non-negative, a spring's stiffness positive and every value
+    /// finite; the app still clamps each one to its control's range.
+    pub fn from_share_string(s: &str) -> Result<Self, String> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(s.trim())
+            .map_err(|e| format!("not a share string: {}", e))?;
+        let mut config: ShareConfig =
+            serde_json::from_slice(&bytes).map_err(|e| format!("corrupt share string: {}", e))?;
+        match config.version {
+            SHARE_VERSION => {}
+            // before drag models, `drag` was always the linear coefficient
+            1 => {
+                config.drag_model = DragModel::Linear;
+                config.version = SHARE_VERSION;
+            }
+            v => {
+                return Err(format!(
+                    "share string version {} is not supported (expected {})",
+                    v, SHARE_VERSION
+                ));
+            }
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let values = [
+            self.length, self.mass, self.drag, self.gravity, self.initial_theta, self.initial_omega,
+            self.simulation_speed, self.pivot_friction, self.drive_amplitude, self.drive_frequency,
+            self.spring_stiffness,
+        ];
+        if !values.iter().all(|v| v.is_finite()) {
+            return Err("share string has a non-finite value".into());
+        }
+        if !(self.length > 0.0 && self.mass > 0.0 && self.gravity > 0.0 && self.simulation_speed > 0.0) {
+            return Err("length, mass, gravity and speed must be positive".into());
+        }
+        if self.drag < 0.0 || self.pivot_friction < 0.0 || self.drive_frequency < 0.0 {
+            return Err("drag, friction and drive frequency can't be negative".into());
+        }
+        if self.elastic && self.spring_stiffness <= 0.0 {
+            return Err("a spring needs a positive stiffness".into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ShareConfig {
+        ShareConfig {
+            version: SHARE_VERSION,
+            length: 1.37,
+            mass: 0.3,
+            drag: 0.125,
+            gravity: 3.71,
+            initial_theta: 2.9,
+            initial_omega: -0.4,
+            simulation_speed: 1.5,
+            integrator: Integrator::Rk45,
+            drag_model: DragModel::Quadratic,
+            pivot_friction: 0.02,
+            drive_enabled: true,
+            drive_amplitude: 1.15,
+            drive_frequency: 2.0 / 3.0,
+            elastic: true,
+            spring_stiffness: 55.0,
+        }
+    }
+
+    #[test]
+    fn round_trips_every_field() {
+        let config = sample();
+        let decoded = ShareConfig::from_share_string(&config.to_share_string()).unwrap();
+        assert_eq!(decoded, config);
+    }
+
+    #[test]
+    fn missing_fields_take_defaults() {
+        let json = format!(
+            r#"{{"version":{},"length":1.0,"mass":1.0,"drag":0.0,"gravity":9.81,"initial_theta":0.5,"simulation_speed":1.0}}"#,
+            SHARE_VERSION
+        );
+        let decoded = ShareConfig::from_share_string(&URL_SAFE_NO_PAD.encode(json)).unwrap();
+        assert_eq!(decoded.initial_omega, 0.0);
+        assert_eq!(decoded.integrator, Integrator::Rk4);
+        assert_eq!(decoded.drag_model, DragModel::Linear);
+        assert!(!decoded.drive_enabled && !decoded.elastic);
+    }
+
+    #[test]
+    fn version_one_reads_drag_as_linear() {
+        let json = r#"{"version":1,"length":1.0,"mass":1.0,"drag":0.3,"gravity":9.81,"initial_theta":0.5,"simulation_speed":1.0,"drag_model":"Quadratic"}"#;
+        let decoded = ShareConfig::from_share_string(&URL_SAFE_NO_PAD.encode(json)).unwrap();
+        assert_eq!(decoded.version, SHARE_VERSION);
+        assert_eq!(decoded.drag, 0.3);
+        assert_eq!(decoded.drag_model, DragModel::Linear);
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        let config = ShareConfig { version: SHARE_VERSION + 1, ..sample() };
+        assert!(ShareConfig::from_share_string(&config.to_share_string()).is_err());
+    }
+
+    #[test]
+    fn unphysical_values_are_rejected() {
+        for config in [
+            ShareConfig { length: 0.0, ..sample() },
+            ShareConfig { gravity: -9.81, ..sample() },
+            ShareConfig { simulation_speed: 0.0, ..sample() },
+            ShareConfig { drag: -0.5, ..sample() },
+            ShareConfig { drive_frequency: -3.0, ..sample() },
+            ShareConfig { spring_stiffness: 0.0, ..sample() },
+        ] {
+            assert!(ShareConfig::from_share_string(&config.to_share_string()).is_err(), "{config:?}");
+        }
+        // a rigid rod doesn't need a stiffness
+        let rigid = ShareConfig { elastic: false, spring_stiffness: 0.0, ..sample() };
+        assert!(ShareConfig::from_share_string(&rigid.to_share_string()).is_ok());
+    }
+}
@@ -0,0 +1,282 @@
+// src/simulation.rs
+
+use std::io;
+use std::path::Path;
+
+use pendulum_core::json::{invalid, load_json, save_json};
+use serde::{Deserialize, Serialize};
+
+use crate::physics::{energy, rk4_step, Drag, DragModel, Drive, Integrator, Params};
+
+/// Pendulum parameters and state with no GUI attached. The app wraps one;
+/// the `--headless` CLI and tests drive it directly.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Simulation {
+    pub length: f32,
+    pub mass: f32,
+    pub drag: f32,
+    pub gravity: f32,
+    #[serde(default)]
+    pub drag_model: DragModel,
+    /// Coulomb friction at the pivot, on top of `drag`.
+    #[serde(default)]
+    pub pivot_friction: f32,
+    pub theta: f32,
+    pub omega: f32,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self {
+            length: 1.0,
+            mass: 1.0,
+            drag: 0.0,
+            gravity: 9.81,
+            drag_model: DragModel::Linear,
+            pivot_friction: 0.0,
+            theta: 0.35,
+            omega: 0.0,
+        }
+    }
+}
+
+impl Simulation {
+    /// Drag terms for the current coefficient and model, plus pivot friction.
+    pub fn drag_terms(&self) -> Drag {
+        self.drag_terms_for(self.drag)
+    }
+
+    /// Drag terms for coefficient `drag` under this pendulum's model, plus
+    /// its pivot friction, for a second pendulum sharing the same pivot setup.
+    pub fn drag_terms_for(&self, drag: f32) -> Drag {
+        Drag { coulomb: self.pivot_friction.max(0.0), ..self.drag_model.drag(drag.max(0.0)) }
+    }
+
+    /// Step parameters for the current settings, kept away from zero
+    /// length, mass and gravity, with no drive.
+    pub fn params(&self) -> Params {
+        Params {
+            l: self.length.max(0.01),
+            m: self.mass.max(1e-6),
+            drag: self.drag_terms(),
+            g: self.gravity.max(0.1),
+            drive: Drive::NONE,
+        }
+    }
+
+    /// Advance one undriven RK4 step of `dt` seconds.
+    pub fn step(&mut self, dt: f32) {
+        (self.theta, self.omega) = rk4_step(self.theta, self.omega, dt, self.params(), 0.0);
+    }
+
+    /// Advance `seconds` in steps of at most `dt`.
+    pub fn run(&mut self, seconds: f32, dt: f32) {
+        let mut remaining = seconds;
+        while remaining > 0.0 {
+            let step = remaining.min(dt);
+            self.step(step);
+            remaining -= step;
+        }
+    }
+
+    /// Potential plus kinetic energy in joules.
+    pub fn total_energy(&self) -> f32 {
+        let (potential, kinetic) = energy(self.theta, self.omega, self.length, self.mass, self.gravity);
+        potential + kinetic
+    }
+}
+
+/// A run as stored in a JSON state file: the simulation, the initial
+/// condition that Reset returns to, the simulated time, and the integrator,
+/// drive, spring and escapement it was running under. Files from before the
+/// drive and spring were saved load as an undriven rigid RK4 run.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunState {
+    #[serde(flatten)]
+    pub sim: Simulation,
+    pub initial_theta: f32,
+    #[serde(default)]
+    pub initial_omega: f32,
+    #[serde(default)]
+    pub time: f32,
+    #[serde(default)]
+    pub integrator: Integrator,
+    #[serde(default = "default_rk45_tolerance")]
+    pub rk45_tolerance: f32,
+    #[serde(default)]
+    pub drive_enabled: bool,
+    #[serde(default)]
+    pub drive_amplitude: f32,
+    #[serde(default)]
+    pub drive_frequency: f32,
+    /// Seconds the drive phase has run; a drag or preset restarts it
+    /// without resetting `time`.
+    #[serde(default)]
+    pub drive_time: f32,
+    /// Spring in place of the rod, with its current length and stretch rate.
+    #[serde(default)]
+    pub elastic: bool,
+    #[serde(default)]
+    pub spring_stiffness: f32,
+    #[serde(default)]
+    pub radius: f32,
+    #[serde(default)]
+    pub radius_rate: f32,
+    #[serde(default)]
+    pub escapement_enabled: bool,
+    #[serde(default)]
+    pub escapement: f32,
+}
+
+fn default_rk45_tolerance() -> f32 {
+    1e-5
+}
+
+impl RunState {
+    /// The saved run's drive, or none while it's switched off.
+    pub fn drive(&self) -> Drive {
+        if self.drive_enabled {
+            Drive { amplitude: self.drive_amplitude, frequency: self.drive_frequency }
+        } else {
+            Drive::NONE
+        }
+    }
+
+    /// Step parameters for the saved run, drive included.
+    pub fn params(&self) -> Params {
+        Params { drive: self.drive(), ..self.sim.params() }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        save_json(self, path)
+    }
+
+    /// Read a state file. Fails unless length, mass and gravity are positive,
+    /// drag, friction, drive frequency and escapement non-negative, the RK45
+    /// tolerance positive, a spring's stiffness and length positive, and
+    /// every value finite.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let s: RunState = load_json(path)?;
+        let sim = &s.sim;
+        let values = [
+            sim.length, sim.mass, sim.drag, sim.gravity, sim.pivot_friction, sim.theta, sim.omega,
+            s.initial_theta, s.initial_omega, s.time, s.rk45_tolerance, s.drive_amplitude, s.drive_frequency,
+            s.drive_time,             s.spring_stiffness, s.radius, s.radius_rate, s.escapement,
+        ];
+        if !values.iter().all(|v| v.is_finite()) {
+            return Err(invalid("state must be finite"));
+        }
+        if !(sim.length > 0.0 && sim.mass > 0.0 && sim.gravity > 0.0) {
+            return Err(invalid("length, mass and gravity must be positive"));
+        }
+        if sim.drag < 0.0 || sim.pivot_friction < 0.0 || s.time < 0.0 {
+            return Err(invalid("drag, friction and time can't be negative"));
+        }
+        if s.drive_frequency < 0.0 || s.drive_time < 0.0 || s.escapement < 0.0 || s.rk45_tolerance <= 0.0 {
+            return Err(invalid("drive frequency, drive time and escapement can't be negative, nor the tolerance zero"));
+        }
+        if s.elastic && !(s.spring_stiffness > 0.0 && s.radius > 0.0) {
+            return Err(invalid("a spring needs a positive stiffness and length"));
+        }
+        Ok(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load `text` as if it were the state file `name` on disk.
+    fn load_text(name: &str, text: &str) -> io::Result<RunState> {
+        let path = std::env::temp_dir().join(format!("{name}-{}.json", std::process::id()));
+        std::fs::write(&path, text).unwrap();
+        let s = RunState::load(&path);
+        let _ = std::fs::remove_file(&path);
+        s
+    }
+
+    fn driven() -> RunState {
+        RunState {
+            sim: Simulation { drag: 0.5, theta: 0.2, ..Simulation::default() },
+            initial_theta: 0.2,
+            initial_omega: 0.0,
+            time: 0.0,
+            integrator: Integrator::Symplectic,
+            rk45_tolerance: 1e-5,
+            drive_enabled: true,
+            drive_amplitude: 1.15,
+            drive_frequency: 2.0 / 3.0,
+            drive_time: 7.25,
+            elastic: false,
+            spring_stiffness: 40.0,
+            radius: 1.0,
+            radius_rate: 0.0,
+            escapement_enabled: false,
+            escapement: 0.05,
+        }
+    }
+
+    /// Advance a rigid saved run the way the app does, drive clock included.
+    fn advance(s: &mut RunState, steps: usize) {
+        let dt = 0.005;
+        for _ in 0..steps {
+            (s.sim.theta, s.sim.omega) = s.integrator.step(s.sim.theta, s.sim.omega, dt, s.params(), s.drive_time);
+            s.time += dt;
+            s.drive_time += dt;
+        }
+    }
+
+    #[test]
+    fn reloaded_driven_run_continues_identically() {
+        let mut saved = driven();
+        advance(&mut saved, 500);
+        let path = std::env::temp_dir().join(format!("driven-run-{}.json", std::process::id()));
+        saved.save(&path).unwrap();
+        let reloaded = RunState::load(&path);
+        let _ = std::fs::remove_file(&path);
+        let mut reloaded = reloaded.unwrap();
+        assert_eq!(reloaded, saved);
+        advance(&mut saved, 2000);
+        advance(&mut reloaded, 2000);
+        assert_eq!(reloaded.sim.theta.to_bits(), saved.sim.theta.to_bits());
+        assert_eq!(reloaded.sim.omega.to_bits(), saved.sim.omega.to_bits());
+    }
+
+    #[test]
+    fn file_without_drive_or_spring_loads_as_undriven_rigid_rk4() {
+        let text = r#"{"length":1.5,"mass":2.0,"drag":0.1,"gravity":9.81,"theta":0.3,"omega":0.0,"initial_theta":0.3}"#;
+        let s = load_text("old-run", text).unwrap();
+        assert_eq!(s.sim.drag_model, DragModel::Linear);
+        assert_eq!((s.initial_omega, s.time), (0.0, 0.0));
+        assert_eq!(s.integrator, Integrator::Rk4);
+        assert_eq!(s.params().drive, Drive::NONE);
+        assert!(!s.elastic && !s.escapement_enabled);
+        assert!(s.rk45_tolerance > 0.0);
+    }
+
+    #[test]
+    fn load_rejects_unphysical_runs() {
+        let base = serde_json::json!({
+            "length": 1.0, "mass": 1.0, "drag": 0.0, "gravity": 9.81, "theta": 0.3, "omega": 0.0, "initial_theta": 0.3,
+        });
+        assert!(load_text("valid-run", &base.to_string()).is_ok());
+        for bad in [
+            serde_json::json!({ "length": 0.0 }),
+            serde_json::json!({ "gravity": -9.81 }),
+            serde_json::json!({ "drag": -0.1 }),
+            serde_json::json!({ "pivot_friction": -1.0 }),
+            serde_json::json!({ "time": -1.0 }),
+            serde_json::json!({ "drive_frequency": -2.0 }),
+            serde_json::json!({ "rk45_tolerance": 0.0 }),
+            serde_json::json!({ "escapement": -0.05 }),
+            serde_json::json!({ "elastic": true, "spring_stiffness": 40.0 }),
+            serde_json::json!({ "elastic": true, "radius": 1.0 }),
+        ] {
+            let mut text = base.clone();
+            text.as_object_mut().unwrap().extend(bad.as_object().unwrap().clone());
+            // it parses, so the load fails on validation rather than syntax
+            assert!(serde_json::from_value::<RunState>(text.clone()).is_ok(), "{bad}");
+            assert!(load_text("invalid-run", &text.to_string()).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,59 @@
+// src/spectrum.rs
+//
+// Frequency content of a recorded signal, for the spectrum plot.
+
+use rustfft::{FftPlanner, num_complex::Complex};
+
+/// Linearly resample `(time, value)` pairs (in increasing time) onto a grid
+/// with spacing `dt`, starting at the first sample.
+pub fn resample_uniform(samples: &[(f32, f32)], dt: f32) -> Vec<f32> {
+    let (Some(&(t0, _)), Some(&(t_end, _))) = (samples.first(), samples.last()) else {
+        return Vec::new();
+    };
+    if dt <= 0.0 || t_end <= t0 {
+        return Vec::new();
+    }
+    let n = ((t_end - t0) / dt).floor() as usize + 1;
+    let mut out = Vec::with_capacity(n);
+    let mut j = 0;
+    for i in 0..n {
+        let t = t0 + i as f32 * dt;
+        while j + 2 < samples.len() && samples[j + 1].0 < t {
+            j += 1;
+        }
+        let (ta, va) = samples[j];
+        let (tb, vb) = samples[(j + 1).min(samples.len() - 1)];
+        let u = if tb > ta { ((t - ta) / (tb - ta)).clamp(0.0, 1.0) } else { 0.0 };
+        out.push(va + (vb - va) * u);
+    }
+    out
+}
+
+/// One-sided magnitude spectrum of a signal sampled every `dt` seconds, as
+/// `(frequency Hz, amplitude)` pairs from DC up to Nyquist; a pure tone peaks
+/// at its own amplitude. The mean is
+/// removed and a Hann window applied so the oscillation peak isn't buried
+/// under the offset or smeared by the window edges.
+pub fn magnitude_spectrum(signal: &[f32], dt: f32) -> Vec<(f32, f32)> {
+    let n = signal.len();
+    if n < 4 || dt <= 0.0 {
+        return Vec::new();
+    }
+    let mean = signal.iter().sum::<f32>() / n as f32;
+    let mut buffer: Vec<Complex<f32>> = signal
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let w = 0.5 - 0.5 * (std::f32::consts::TAU * i as f32 / (n - 1) as f32).cos();
+            Complex::new((v - mean) * w, 0.0)
+        })
+        .collect();
+    FftPlanner::new().plan_fft_forward(n).process(&mut buffer);
+    let df = 1.0 / (n as f32 * dt);
+    buffer[..=n / 2]
+        .iter()
+        .enumerate()
+        // 2 for the one-sided spectrum, 2 for the Hann window's coherent gain
+        .map(|(k, c)| (k as f32 * df, 4.0 * c.norm() / n as f32))
+        .collect()
+}
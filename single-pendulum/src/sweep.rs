@@ -0,0 +1,80 @@
+// src/sweep.rs
+//
+// Batch runs over one parameter, for curves like period against amplitude
+// or the bifurcation diagram of the driven pendulum.
+
+use crate::physics::{measure_period, rk4_step, wrap_angle, Drive, Params};
+use crate::simulation::Simulation;
+
+/// `count` values evenly spaced from `from` to `to`, both included.
+pub fn linspace(from: f32, to: f32, count: usize) -> impl Iterator<Item = f32> {
+    let last = count.max(2) - 1;
+    (0..count).map(move |i| from + (to - from) * i as f32 / last as f32)
+}
+
+/// Measured period at `count` release angles from `from` to `to` (radians),
+/// each a fresh run from rest with `sim`'s length, mass, drag and gravity.
+/// Returns `(amplitude, period)` pairs; amplitudes where no period is
+/// measured (overdamped, or no swing within a minute) are left out.
+pub fn period_sweep(sim: &Simulation, from: f32, to: f32, count: usize) -> Vec<(f32, f32)> {
+    let Params { l, m, drag, g, .. } = sim.params();
+    linspace(from, to, count)
+        .filter_map(|amplitude| {
+            let period = measure_period(amplitude, l, m, drag, g)?;
+            Some((amplitude, period))
+        })
+        .collect()
+}
+
+/// How finely and how long to run a bifurcation sweep. Cost grows with
+/// `amplitudes × (transient + samples) × steps_per_period`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BifurcationSettings {
+    /// Drive torque range in N·m.
+    pub from: f32,
+    pub to: f32,
+    /// Number of drive amplitudes across the range.
+    pub amplitudes: usize,
+    /// Drive periods discarded before sampling, to let the motion settle.
+    pub transient: usize,
+    /// Drive periods sampled once each, after the transient.
+    pub samples: usize,
+    /// RK4 steps per drive period.
+    pub steps_per_period: usize,
+}
+
+impl Default for BifurcationSettings {
+    fn default() -> Self {
+        Self { from: 0.0, to: 1.0, amplitudes: 120, transient: 150, samples: 40, steps_per_period: 100 }
+    }
+}
+
+/// Bifurcation diagram of `sim` driven at `frequency` rad/s: for each drive
+/// amplitude in `settings`, run from `sim`'s state past the transient, then
+/// take θ (wrapped to [-π, π]) once per drive period. Returns
+/// `(amplitude, θ)` points; a period-n orbit shows as n points per amplitude
+/// and chaos as a smear. Empty if `frequency` is not positive.
+pub fn bifurcation(sim: &Simulation, frequency: f32, settings: &BifurcationSettings) -> Vec<(f32, f32)> {
+    if frequency <= 0.0 || settings.samples == 0 {
+        return Vec::new();
+    }
+    let base = sim.params();
+    let steps = settings.steps_per_period.max(4);
+    let dt = std::f32::consts::TAU / frequency / steps as f32;
+    let mut points = Vec::with_capacity(settings.amplitudes * settings.samples);
+    for amplitude in linspace(settings.from, settings.to, settings.amplitudes) {
+        let p = Params { drive: Drive { amplitude, frequency }, ..base };
+        let (mut theta, mut omega) = (sim.theta, sim.omega);
+        for period in 0..settings.transient + settings.samples {
+            // restart the clock each period: the drive is periodic, and a
+            // growing f32 time would lose phase precision over long runs
+            for i in 0..steps {
+                (theta, omega) = rk4_step(theta, omega, dt, p, i as f32 * dt);
+            }
+            if period >= settings.transient {
+                points.push((amplitude, wrap_angle(theta)));
+            }
+        }
+    }
+    points
+}
@@ -1,9 +1,56 @@
 // src/ui.rs
 
-use eframe::egui::{Align2, Color32, FontId, Painter, Pos2, Rect, Stroke};
+use eframe::egui::{Align2, Color32, FontId, Painter, Pos2, Rect, Response, Shape, Stroke, Vec2};
 
 use crate::app::PendulumApp;
 
+/// How a bob's drawn radius grows with its mass. Every mode draws a 2 kg bob
+/// at 14 px and clamps the result so extreme masses stay visible.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BobScale {
+    /// Radius ∝ mass.
+    Linear,
+    /// Radius ∝ √mass, so the disc's area tracks the mass.
+    #[default]
+    SquareRoot,
+    /// Radius ∝ ∛mass, as for a solid sphere of fixed density.
+    CubeRoot,
+}
+
+impl BobScale {
+    pub const ALL: [BobScale; 3] = [BobScale::Linear, BobScale::SquareRoot, BobScale::CubeRoot];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BobScale::Linear => "Linear",
+            BobScale::SquareRoot => "Square root",
+            BobScale::CubeRoot => "Cube root (volume)",
+        }
+    }
+
+    /// Drawn radius in pixels for a bob of `mass` kg.
+    pub fn radius(self, mass: f32) -> f32 {
+        let ratio = (mass / 2.0).max(0.0);
+        let factor = match self {
+            BobScale::Linear => ratio.clamp(0.25, 3.0),
+            BobScale::SquareRoot => ratio.sqrt().clamp(0.6, 2.0),
+            BobScale::CubeRoot => ratio.cbrt().clamp(0.4, 2.5),
+        };
+        14.0 * factor
+    }
+}
+
+/// Angle from the pivot (the centre of the canvas) to the pointer while the
+/// canvas is being dragged, measured like `theta`: zero straight down,
+/// positive to the right.
+pub fn dragged_angle(resp: &Response) -> Option<f32> {
+    if !resp.dragged() {
+        return None;
+    }
+    let offset = resp.interact_pointer_pos()? - resp.rect.center();
+    (offset.length() > 1.0).then(|| offset.x.atan2(offset.y))
+}
+
 pub fn draw_pendulum(app: &PendulumApp, painter: &Painter, rect: Rect) {
     let bg = if app.dark_mode {
         Color32::from_gray(18)
@@ -20,48 +67,90 @@ pub fn draw_pendulum(app: &PendulumApp, painter: &Painter, rect: Rect) {
 
     let center = rect.center();
     let scale = (rect.height() * 0.42).max(88.0); // slightly tighter than before
-    let length_px = (app.length * scale).clamp(30.0, rect.height() * 0.85);
+    let length_px = (app.rod_length() * scale).clamp(30.0, rect.height() * 0.85);
 
     let bob = Pos2::new(
-        center.x + length_px * app.theta.sin(),
-        center.y + length_px * app.theta.cos(),
+        center.x + length_px * app.sim.theta.sin(),
+        center.y + length_px * app.sim.theta.cos(),
     );
-    let speed_ratio = (app.omega.abs() / 5.0).min(1.0);
-    let rod_color = if speed_ratio > 0.5 {
-        Color32::from_rgb(
-            (255.0 * speed_ratio) as u8,
-            (100.0 * (1.0 - speed_ratio)) as u8,
-            100,
-        )
-    } else {
-        Color32::from_rgb(
-            100,
-            (150.0 + 105.0 * speed_ratio) as u8,
-            255,
-        )
-    };
+    let palette = app.palette();
+    let speed_ratio = (app.sim.omega.abs() / 5.0).min(1.0);
+    let rod_color = palette.rod_color(speed_ratio);
+
+    if let Some(ghost) = &app.preview {
+        let ghost_px = (ghost.length * scale).clamp(30.0, rect.height() * 0.85);
+        let ghost_bob = Pos2::new(
+            center.x + ghost_px * ghost.theta.sin(),
+            center.y + ghost_px * ghost.theta.cos(),
+        );
+        let faint = Color32::from_rgba_unmultiplied(160, 200, 255, 70);
+        painter.line_segment([center, ghost_bob], Stroke::new(3.0, faint));
+        painter.circle_filled(
+            ghost_bob,
+            app.bob_scale.radius(ghost.mass),
+            faint,
+        );
+    }
+
+    if let Some(b) = &app.compare {
+        let b_px = (b.length * scale).clamp(30.0, rect.height() * 0.85);
+        let b_bob = Pos2::new(center.x + b_px * b.theta.sin(), center.y + b_px * b.theta.cos());
+        let c = palette.compare;
+        let faint = Color32::from_rgba_unmultiplied(c.r(), c.g(), c.b(), 110);
+        painter.line_segment([center, b_bob], Stroke::new(3.0, faint));
+        painter.circle_filled(b_bob, app.bob_scale.radius(b.mass), faint);
+    }
 
-    painter.line_segment([center, bob], Stroke::new(4.0, rod_color));
+    if app.elastic {
+        draw_spring(painter, center, bob, Stroke::new(2.5 * palette.stroke_scale, rod_color));
+    } else {
+        painter.line_segment([center, bob], Stroke::new(4.0 * palette.stroke_scale, rod_color));
+    }
     painter.circle_filled(center, 6.0, Color32::from_gray(200));
-    let bob_radius = 14.0 * (app.mass / 2.0).sqrt().clamp(0.6, 2.0);
-    painter.circle_filled(bob, bob_radius, Color32::from_rgb(220, 70, 70));
+    let bob_radius = app.bob_scale.radius(app.sim.mass);
+    painter.circle_filled(bob, bob_radius, palette.bob);
     painter.circle_stroke(
         bob,
         bob_radius,
-        Stroke::new(2.0, Color32::from_rgb(180, 60, 60)),
+        Stroke::new(2.0 * palette.stroke_scale, palette.bob_outline),
     );
 
+    if app.show_forces {
+        draw_forces(app, painter, bob, bob - center);
+    }
+
     let (_p, _k, energy) = app.calculate_energy();
-    let period = 2.0 * std::f32::consts::PI * (app.length / app.gravity).sqrt();
+    let period = 2.0 * std::f32::consts::PI * (app.sim.length / app.sim.gravity).sqrt();
+    let measured = app
+        .measured_period()
+        .map_or_else(|| "—".to_owned(), |t| format!("{t:.2}s"));
     let info = format!(
-        "L:{:.2}m • θ:{:.1}° • ω:{:.1}°/s • T:{:.2}s • E:{:.2}J",
-        app.length,
-        app.theta.to_degrees(),
-        app.omega.to_degrees(),
+        "L:{:.2}m • θ:{:.1}° • ω:{:.1}°/s • T₀:{:.2}s • T:{} • E:{:.2}J",
+        app.rod_length(),
+        app.sim.theta.to_degrees(),
+        app.sim.omega.to_degrees(),
         period,
+        measured,
         energy
     );
 
+    // natural, damped (linear drag only; underdamped only) and measured frequencies
+    let natural = (app.sim.gravity / app.sim.length.max(0.01)).sqrt();
+    let hz = |w: f32| w / std::f32::consts::TAU;
+    let gamma = app.sim.drag_terms().linear / (2.0 * app.sim.mass.max(1e-6));
+    let damped = if gamma <= 0.0 {
+        None
+    } else {
+        Some((natural * natural - gamma * gamma).sqrt()).filter(|w| w.is_finite())
+    };
+    let show = |w: Option<f32>| w.map_or_else(|| "—".to_owned(), |w| format!("{:.3}Hz ({:.2}rad/s)", hz(w), w));
+    let frequencies = format!(
+        "f₀:{} • f_d:{} • f:{}",
+        show(Some(natural)),
+        if gamma <= 0.0 { "= f₀".to_owned() } else { show(damped) },
+        show(app.measured_period().map(|t| std::f32::consts::TAU / t)),
+    );
+
     // tighter info placement (less vertical padding)
     painter.text(
         Pos2::new(rect.left() + 8.0, rect.top() + 8.0),
@@ -70,6 +159,13 @@ pub fn draw_pendulum(app: &PendulumApp, painter: &Painter, rect: Rect) {
         FontId::proportional(13.0),
         text,
     );
+    painter.text(
+        Pos2::new(rect.left() + 8.0, rect.top() + 26.0),
+        Align2::LEFT_TOP,
+        frequencies,
+        FontId::proportional(12.0),
+        text,
+    );
     painter.text(
         Pos2::new(rect.right() - 10.0, rect.top() + 8.0),
         Align2::RIGHT_TOP,
@@ -77,4 +173,71 @@ pub fn draw_pendulum(app: &PendulumApp, painter: &Painter, rect: Rect) {
         FontId::monospace(11.0),
         text,
     );
+}
+
+/// Free-body diagram at the bob: gravity, rod tension, and gravity's
+/// tangential component, plus the net centripetal force. Arrows are scaled
+/// so the bob's weight is 60 px long; drag and the drive are left out.
+fn draw_forces(app: &PendulumApp, painter: &Painter, bob: Pos2, rod: Vec2) {
+    let palette = app.palette();
+    let (m, g, th, w) = (app.sim.mass, app.sim.gravity, app.sim.theta, app.sim.omega);
+    let r = app.rod_length();
+    let weight = m * g;
+    let px_per_newton = 60.0 / weight.max(1e-6);
+    // unit vectors in screen space (y down): outward along the rod, and
+    // along the swing in the direction of increasing θ
+    let outward = rod / rod.length().max(1e-6);
+    let along = Vec2::new(outward.y, -outward.x);
+
+    let centripetal = m * r * w * w;
+    let tension = if app.elastic {
+        let spring = app.spring();
+        spring.stiffness * (r - spring.rest_length)
+    } else {
+        weight * th.cos() + centripetal
+    };
+    let forces = [
+        ("mg", Vec2::new(0.0, weight), palette.force_gravity),
+        ("T", -outward * tension, palette.force_tension),
+        ("mg sinθ", -along * weight * th.sin(), palette.force_tangential),
+        ("mrω²", -outward * centripetal, palette.force_centripetal),
+    ];
+    for (label, force, color) in forces {
+        let v = force * px_per_newton;
+        if v.length() < 2.0 {
+            continue;
+        }
+        painter.arrow(bob, v, Stroke::new(2.0 * palette.stroke_scale, color));
+        painter.text(
+            bob + v + v.normalized() * 10.0,
+            Align2::CENTER_CENTER,
+            label,
+            FontId::proportional(12.0),
+            color,
+        );
+    }
+}
+
+/// Zigzag coil from `from` to `to` with straight leads at both ends, so the
+/// coil visibly compresses and stretches with the rod length.
+fn draw_spring(painter: &Painter, from: Pos2, to: Pos2, stroke: Stroke) {
+    const COILS: usize = 12;
+    let span = to - from;
+    let length = span.length();
+    if length < 1.0 {
+        return;
+    }
+    let dir = span / length;
+    let normal = Vec2::new(-dir.y, dir.x);
+    let lead = (length * 0.1).min(14.0);
+    let mut points = vec![from, from + dir * lead];
+    let coil = length - 2.0 * lead;
+    for i in 0..COILS * 2 {
+        let along = lead + coil * (i as f32 + 0.5) / (COILS * 2) as f32;
+        let side = if i % 2 == 0 { 8.0 } else { -8.0 };
+        points.push(from + dir * along + normal * side);
+    }
+    points.push(to - dir * lead);
+    points.push(to);
+    painter.add(Shape::line(points, stroke));
 }
\ No newline at end of file